@@ -0,0 +1,149 @@
+//! Derive macros for [as-ffi-bindings](https://github.com/cppccn/as-ffi-bindings).
+//!
+//! The macros are re-exported by `as_ffi_bindings` and shouldn't be used
+//! from this crate directly.
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Lit, Meta, NestedMeta};
+
+/// Map a struct with named fields on the fields of an AssemblyScript class.
+///
+/// The fields must be declared in the same order as in the class, each field
+/// type implements `Element`: numbers, `bool`, pointers like `StringPtr` or
+/// `ArrayPtr<T>`, and `Option<P>` for nullable references.
+///
+/// The class id, as given by `idof<T>()` in the guest, is needed to allocate
+/// instances from the host:
+///
+/// ```ignore
+/// #[derive(AsClass)]
+/// #[as_class(id = 3)]
+/// struct MyStruct {
+///     v: i32,
+///     a: i32,
+/// }
+/// ```
+///
+/// A `MyStructPtr` alias of `ClassPtr<MyStruct>` is declared next to the
+/// struct.
+#[proc_macro_derive(AsClass, attributes(as_class))]
+pub fn derive_as_class(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match as_class(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn as_class(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "AsClass needs a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "AsClass can only be derived for structs",
+            ))
+        }
+    };
+    let id = match class_id(&input)? {
+        Some(id) => quote!(::core::option::Option::Some(#id)),
+        None => quote!(::core::option::Option::None),
+    };
+
+    let name = &input.ident;
+    let class_name = name.to_string();
+    let names: Vec<_> = fields.iter().map(|field| &field.ident).collect();
+    let types: Vec<_> = fields.iter().map(|field| &field.ty).collect();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    // Type aliases can't carry the bounds of generic structs
+    let alias = if input.generics.params.is_empty() {
+        let vis = &input.vis;
+        let ptr = format_ident!("{}Ptr", name);
+        let doc = format!("Pointer to an instance of the class `{}`.", name);
+        quote! {
+            #[doc = #doc]
+            #vis type #ptr = ::as_ffi_bindings::ClassPtr<#name>;
+        }
+    } else {
+        quote!()
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::as_ffi_bindings::AsClass for #name #ty_generics #where_clause {
+            const ID: ::core::option::Option<u32> = #id;
+            const NAME: &'static str = #class_name;
+
+            fn size() -> u32 {
+                let mut fields = ::as_ffi_bindings::ClassFields::new(0);
+                #(fields.field::<#types>();)*
+                fields.size()
+            }
+
+            fn read_fields<M: ::as_ffi_bindings::MemoryAccess + ?::core::marker::Sized>(
+                fields: &mut ::as_ffi_bindings::ClassFields,
+                memory: &M,
+            ) -> ::core::result::Result<Self, ::as_ffi_bindings::Error> {
+                ::core::result::Result::Ok(Self {
+                    #(#names: fields.read::<#types, M>(memory)?,)*
+                })
+            }
+
+            fn write_fields<M: ::as_ffi_bindings::MemoryAccess + ?::core::marker::Sized>(
+                &self,
+                fields: &mut ::as_ffi_bindings::ClassFields,
+                memory: &M,
+            ) -> ::core::result::Result<(), ::as_ffi_bindings::Error> {
+                #(fields.write::<#types, M>(&self.#names, memory)?;)*
+                ::core::result::Result::Ok(())
+            }
+        }
+
+        #alias
+    })
+}
+
+/// Read the `id` of `#[as_class(id = 3)]`.
+fn class_id(input: &DeriveInput) -> syn::Result<Option<u32>> {
+    let mut id = None;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("as_class"))
+    {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => {
+                return Err(syn::Error::new_spanned(
+                    meta,
+                    "expected #[as_class(id = ..)]",
+                ))
+            }
+        };
+        for nested in list.nested {
+            match nested {
+                NestedMeta::Meta(Meta::NameValue(value)) if value.path.is_ident("id") => {
+                    match value.lit {
+                        Lit::Int(lit) => id = Some(lit.base10_parse()?),
+                        lit => return Err(syn::Error::new_spanned(lit, "expected a class id")),
+                    }
+                }
+                nested => {
+                    return Err(syn::Error::new_spanned(
+                        nested,
+                        "unknown attribute, expected `id`",
+                    ))
+                }
+            }
+        }
+    }
+    Ok(id)
+}
@@ -0,0 +1,402 @@
+//! Generate typed bindings from the declaration file of a module, the `.d.ts`
+//! emitted by `asc --bindings`. The bindings use `as-ffi-bindings` and
+//! wasmer, but the generator doesn't depend on them, so a build script can
+//! use it without building wasmer.
+use std::io;
+use std::path::Path;
+
+/// Exported functions of a module, read from its `.d.ts`.
+///
+/// The generated struct gets the exports of an instance once, then has a
+/// method per exported function, with the pointer types of this crate for
+/// strings, buffers and collections. In `build.rs`, with `as-ffi-bindings-gen`
+/// in the `[build-dependencies]`:
+///
+/// ```ignore
+/// use as_ffi_bindings_gen::Bindings;
+///
+/// let out = Path::new(&std::env::var("OUT_DIR")?).join("release.rs");
+/// Bindings::from_file("build/release.d.ts")?.write(out)?;
+/// println!("cargo:rerun-if-changed=build/release.d.ts");
+/// ```
+///
+/// Then in the crate:
+///
+/// ```ignore
+/// include!(concat!(env!("OUT_DIR"), "/release.rs"));
+///
+/// let release = Release::new(&instance, &store)?;
+/// let message: StringPtr = release.get_string(&mut store)?;
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct Bindings {
+    /// Name of the generated struct
+    pub name: String,
+    pub functions: Vec<ExportFn>,
+}
+
+/// An exported function, with the AssemblyScript types of its parameters
+/// and result.
+#[derive(Debug, PartialEq)]
+pub struct ExportFn {
+    pub name: String,
+    pub params: Vec<(String, String)>,
+    pub result: String,
+}
+
+impl Bindings {
+    /// Read the declaration file at `path`. The struct is named after the
+    /// file, `Release` for `release.d.ts`.
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let file_name = path.file_name().and_then(|name| name.to_str());
+        let stem = file_name
+            .map(|name| name.trim_end_matches(".d.ts").trim_end_matches(".ts"))
+            .unwrap_or("module");
+        Ok(Self::parse(
+            &camel_case(stem),
+            &std::fs::read_to_string(path)?,
+        ))
+    }
+
+    /// Parse the declarations of the exported functions. Their types are
+    /// taken from the `@param` and `@returns` tags of the doc comments,
+    /// which give the AssemblyScript types, or from the signature otherwise.
+    /// The runtime exports, like `__new`, are left to `EnvMut`.
+    pub fn parse(name: &str, declarations: &str) -> Self {
+        let mut functions = Vec::new();
+        let mut doc = "";
+        let mut rest = declarations;
+        while let Some(start) = rest.find(|c: char| !c.is_whitespace()) {
+            rest = &rest[start..];
+            if let Some(comment) = rest.strip_prefix("/*") {
+                let end = comment.find("*/").map_or(comment.len(), |end| end + 2);
+                doc = &comment[..end];
+                rest = &comment[end..];
+                continue;
+            }
+            let end = statement_end(rest);
+            if let Some(function) = parse_function(&rest[..end], doc) {
+                if !function.name.starts_with("__") {
+                    functions.push(function);
+                }
+            }
+            doc = "";
+            rest = &rest[end..];
+        }
+        Self {
+            name: name.to_string(),
+            functions,
+        }
+    }
+
+    /// Generate the Rust module.
+    pub fn to_rust(&self) -> String {
+        let mut fields = String::new();
+        let mut exports = String::new();
+        let mut methods = String::new();
+        for function in &self.functions {
+            let field = ident(&function.name);
+            fields += &format!(
+                "    {}: ::wasmer::TypedFunction<{}, {}>,\n",
+                field,
+                native_params(function),
+                native_type(&function.result)
+            );
+            exports += &format!(
+                "            {}: instance.exports.get_typed_function(store, {:?})?,\n",
+                field, function.name
+            );
+
+            let mut signature = Vec::new();
+            let mut params = String::new();
+            let mut args = vec!["store".to_string()];
+            for (name, ty) in &function.params {
+                signature.push(format!("{}: {}", name, ty));
+                params += &format!(", {}: {}", ident(name), rust_type(ty));
+                args.push(match is_bool(ty) || is_narrow(ty) {
+                    true => format!("{} as i32", ident(name)),
+                    false => ident(name),
+                });
+            }
+            let call = format!("self.{}.call({})", field, args.join(", "));
+            methods += &format!(
+                "\n    /// `{}({}): {}`\n    pub fn {}(&self, store: &mut impl ::wasmer::AsStoreMut{}) -> Result<{}, ::wasmer::RuntimeError> {{\n        {}\n    }}\n",
+                function.name,
+                signature.join(", "),
+                function.result,
+                field,
+                params,
+                rust_type(&function.result),
+                match function.result.as_str() {
+                    result if is_bool(result) => format!("{}.map(|value| value != 0)", call),
+                    result if is_narrow(result) => {
+                        format!("{}.map(|value| value as {})", call, rust_type(result))
+                    }
+                    _ => call,
+                }
+            );
+        }
+        format!(
+            "// Generated by as-ffi-bindings, do not edit.
+
+/// Exported functions of the `{name}` module.
+pub struct {name} {{
+{fields}}}
+
+impl {name} {{
+    pub fn new(
+        instance: &::wasmer::Instance,
+        store: &impl ::wasmer::AsStoreRef,
+    ) -> Result<Self, ::wasmer::ExportError> {{
+        Ok(Self {{
+{exports}        }})
+    }}
+{methods}}}
+",
+            name = self.name,
+            fields = fields,
+            exports = exports,
+            methods = methods
+        )
+    }
+
+    /// Write the Rust module to `path`.
+    pub fn write(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, self.to_rust())
+    }
+}
+
+/// Length of the statement at the start of `source`, up to its `;` or its
+/// block.
+fn statement_end(source: &str) -> usize {
+    let mut depth = 0;
+    let mut previous = ' ';
+    for (i, c) in source.char_indices() {
+        match c {
+            '(' | '<' | '[' => depth += 1,
+            // Not the arrow of a function type
+            '>' if previous == '=' => {}
+            ')' | '>' | ']' => depth -= 1,
+            '{' if depth == 0 => return i + 1,
+            ';' | '\n' if depth == 0 => return i + 1,
+            _ => {}
+        }
+        previous = c;
+    }
+    source.len()
+}
+
+/// Parse `export declare function name(a: T): R;`, or `export function` in
+/// the declarations of older compilers.
+fn parse_function(statement: &str, doc: &str) -> Option<ExportFn> {
+    let statement = statement.strip_prefix("export ")?.trim_start();
+    let statement = statement.strip_prefix("declare ").unwrap_or(statement);
+    let statement = statement.strip_prefix("function ")?.trim();
+    let open = statement.find('(')?;
+    let close = open + statement[open..].rfind(')')?;
+    let name = statement[..open].trim().to_string();
+    let result = statement[close + 1..]
+        .trim()
+        .trim_end_matches(';')
+        .trim_start_matches(':')
+        .trim();
+    let params = split_top_level(&statement[open + 1..close])
+        .into_iter()
+        .filter_map(|param| {
+            let (name, ty) = param.split_once(':')?;
+            let name = name.trim().trim_end_matches('?').to_string();
+            let ty = doc_tag(doc, "@param", Some(&name)).unwrap_or_else(|| ty.trim().to_string());
+            Some((name, ty))
+        })
+        .collect();
+    let result = doc_tag(doc, "@returns", None).unwrap_or_else(|| match result {
+        "" => "void".to_string(),
+        result => result.to_string(),
+    });
+    Some(ExportFn {
+        name,
+        params,
+        result,
+    })
+}
+
+/// Type between backquotes of a tag, `@param a `i32`` or `@returns `i32``.
+fn doc_tag(doc: &str, tag: &str, name: Option<&str>) -> Option<String> {
+    doc.lines().find_map(|line| {
+        let line = line.trim().trim_start_matches('*').trim();
+        let mut rest = line.strip_prefix(tag)?.trim_start();
+        if let Some(name) = name {
+            rest = rest.strip_prefix(name)?;
+            if !rest.starts_with(char::is_whitespace) {
+                return None;
+            }
+        }
+        let rest = rest.trim_start().strip_prefix('`')?;
+        Some(rest[..rest.find('`')?].to_string())
+    })
+}
+
+/// Split at the commas outside of brackets.
+fn split_top_level(list: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in list.char_indices() {
+        match c {
+            '(' | '<' | '[' | '{' => depth += 1,
+            ')' | '>' | ']' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                items.push(list[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(list[start..].trim());
+    items.retain(|item| !item.is_empty());
+    items
+}
+
+/// Name of a type without its path nor nullability, and its type arguments:
+/// `~lib/array/Array<~lib/string/String> | null` is `Array` of
+/// `~lib/string/String`.
+fn split_type(ty: &str) -> (&str, Vec<&str>) {
+    let ty = ty.trim();
+    let ty = ty.strip_suffix("| null").unwrap_or(ty).trim();
+    let (path, args) = match ty.find('<') {
+        Some(open) if ty.ends_with('>') => {
+            (&ty[..open], split_top_level(&ty[open + 1..ty.len() - 1]))
+        }
+        _ => (ty, Vec::new()),
+    };
+    let name = path.rsplit('/').next().unwrap_or(path).trim();
+    (name, args)
+}
+
+fn is_bool(ty: &str) -> bool {
+    matches!(split_type(ty).0, "bool" | "boolean")
+}
+
+/// Integers narrower than the `i32` they're given as to wasm.
+fn is_narrow(ty: &str) -> bool {
+    matches!(split_type(ty).0, "i8" | "u8" | "i16" | "u16")
+}
+
+/// Type of a parameter or a result in the generated methods.
+fn rust_type(ty: &str) -> String {
+    match split_type(ty) {
+        ("bool" | "boolean", _) => "bool".to_string(),
+        (name @ ("i8" | "u8" | "i16" | "u16"), _) => name.to_string(),
+        _ => native_type(ty),
+    }
+}
+
+/// Type of a parameter or a result for `TypedFunction`.
+fn native_type(ty: &str) -> String {
+    let (name, args) = split_type(ty);
+    let arg = |i: usize| args.get(i).map_or("i32".to_string(), |ty| element_type(ty));
+    match name {
+        "void" => "()".to_string(),
+        "i32" | "u32" | "i64" | "u64" | "f32" | "f64" => name.to_string(),
+        "i8" | "u8" | "i16" | "u16" | "isize" | "bool" | "boolean" => "i32".to_string(),
+        "usize" => "u32".to_string(),
+        "number" => "f64".to_string(),
+        "bigint" => "i64".to_string(),
+        "String" | "string" => "::as_ffi_bindings::StringPtr".to_string(),
+        "ArrayBuffer" => "::as_ffi_bindings::BufferPtr".to_string(),
+        "Array" => format!("::as_ffi_bindings::ArrayPtr<{}>", arg(0)),
+        "StaticArray" => format!("::as_ffi_bindings::StaticArrayPtr<{}>", arg(0)),
+        "Set" => format!("::as_ffi_bindings::SetPtr<{}>", arg(0)),
+        "Map" => format!("::as_ffi_bindings::MapPtr<{}, {}>", arg(0), arg(1)),
+        name => match typed_array(name) {
+            Some(element) => format!("::as_ffi_bindings::TypedArrayPtr<{}>", element),
+            _ => "::as_ffi_bindings::AnyPtr".to_string(),
+        },
+    }
+}
+
+/// Type of the elements of a collection, as stored in memory.
+fn element_type(ty: &str) -> String {
+    let nullable = ty.trim().ends_with("| null");
+    let element = match split_type(ty).0 {
+        "bool" | "boolean" => "bool".to_string(),
+        name @ ("i8" | "u8" | "i16" | "u16") => name.to_string(),
+        "isize" => "i32".to_string(),
+        "usize" => "u32".to_string(),
+        _ => native_type(ty),
+    };
+    match nullable {
+        true => format!("Option<{}>", element),
+        false => element,
+    }
+}
+
+fn typed_array(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "Int8Array" => "i8",
+        "Uint8Array" | "Uint8ClampedArray" => "u8",
+        "Int16Array" => "i16",
+        "Uint16Array" => "u16",
+        "Int32Array" => "i32",
+        "Uint32Array" => "u32",
+        "Int64Array" => "i64",
+        "Uint64Array" => "u64",
+        "Float32Array" => "f32",
+        "Float64Array" => "f64",
+        _ => return None,
+    })
+}
+
+/// Snake case Rust identifier of an export or a parameter.
+fn ident(name: &str) -> String {
+    let mut ident = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 && !ident.ends_with('_') {
+                ident.push('_');
+            }
+            ident.push(c.to_ascii_lowercase());
+        } else if c.is_ascii_alphanumeric() || c == '_' {
+            ident.push(c);
+        } else {
+            ident.push('_');
+        }
+    }
+    match ident.as_str() {
+        "as" | "break" | "const" | "continue" | "crate" | "else" | "enum" | "extern" | "false"
+        | "fn" | "for" | "if" | "impl" | "in" | "let" | "loop" | "match" | "mod" | "move"
+        | "mut" | "pub" | "ref" | "return" | "static" | "struct" | "trait" | "true" | "type"
+        | "unsafe" | "use" | "where" | "while" | "async" | "await" | "dyn" | "try" => {
+            format!("r#{}", ident)
+        }
+        // Taken by the constructor and the receiver
+        "new" | "self" | "super" => format!("{}_", ident),
+        _ => ident,
+    }
+}
+
+fn camel_case(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+fn native_params(function: &ExportFn) -> String {
+    let types: Vec<String> = function
+        .params
+        .iter()
+        .map(|(_, ty)| native_type(ty))
+        .collect();
+    match types.len() {
+        1 => types[0].clone(),
+        _ => format!("({})", types.join(", ")),
+    }
+}
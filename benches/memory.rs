@@ -0,0 +1,74 @@
+use as_ffi_bindings::{BufferPtr, MemoryAccess, Read};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::convert::TryFrom;
+use wasmer::{Memory, MemoryType, MemoryView, Store};
+
+const SIZES: [usize; 5] = [1 << 10, 64 << 10, 1 << 20, 16 << 20, 64 << 20];
+
+// The buffers start after their header
+const OFFSET: u32 = 16;
+
+fn memory() -> (Store, Memory) {
+    // 64 MB and one more page for the header
+    let pages = u32::try_from((64 << 20) / 65536 + 1).unwrap();
+    let mut store = Store::default();
+    let memory = Memory::new(&mut store, MemoryType::new(pages, None, false)).unwrap();
+    (store, memory)
+}
+
+/// Copies byte by byte, the baseline of the bulk copies.
+fn write_each(view: &MemoryView, offset: u32, bytes: &[u8]) {
+    for (i, byte) in bytes.iter().enumerate() {
+        view.write_u8(u64::from(offset) + i as u64, *byte).unwrap();
+    }
+}
+
+fn read_each(view: &MemoryView, offset: u32, buf: &mut [u8]) {
+    for (i, byte) in buf.iter_mut().enumerate() {
+        *byte = view.read_u8(u64::from(offset) + i as u64).unwrap();
+    }
+}
+
+fn write(c: &mut Criterion) {
+    let (store, memory) = memory();
+    let view = memory.view(&store);
+    let mut group = c.benchmark_group("write");
+    group.sample_size(10);
+    for size in SIZES {
+        let bytes = vec![0x2a; size];
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::new("each", size), &bytes, |b, bytes| {
+            b.iter(|| write_each(&view, OFFSET, bytes))
+        });
+        group.bench_with_input(BenchmarkId::new("bulk", size), &bytes, |b, bytes| {
+            b.iter(|| view.write_bytes(OFFSET, bytes).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn read(c: &mut Criterion) {
+    let (store, memory) = memory();
+    let view = memory.view(&store);
+    let mut group = c.benchmark_group("read");
+    group.sample_size(10);
+    for size in SIZES {
+        let mut buf = vec![0; size];
+        view.write_bytes(OFFSET - 4, &u32::try_from(size).unwrap().to_le_bytes())
+            .unwrap();
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_function(BenchmarkId::new("each", size), |b| {
+            b.iter(|| read_each(&view, OFFSET, &mut buf))
+        });
+        group.bench_function(BenchmarkId::new("bulk", size), |b| {
+            b.iter(|| view.read_bytes(OFFSET, &mut buf).unwrap())
+        });
+        group.bench_function(BenchmarkId::new("BufferPtr::read", size), |b| {
+            b.iter(|| BufferPtr::new(OFFSET).read(&view).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, write, read);
+criterion_main!(benches);
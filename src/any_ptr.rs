@@ -1,13 +1,15 @@
-use super::{Env, Memory, Read, Write};
+use super::{Error, MemoryAccess, Pinned, Pointer, Read, Runtime, Write};
 use crate::{BufferPtr, StringPtr};
-use std::convert::{TryFrom, TryInto};
-use wasmer::{Array, FromToNativeWasmType, Value, WasmPtr};
+use std::convert::TryFrom;
+use wasmer::FromToNativeWasmType;
 
-use crate::tools::export_asr;
+use crate::tools::{
+    alloc, collect, pin, read_bytes, read_u32, release, unpin, write_bytes, write_u32,
+};
 
 // todo: should I implement Any ?
 #[derive(Clone, Copy)]
-pub struct AnyPtr(WasmPtr<u8, Array>);
+pub struct AnyPtr(u32);
 pub struct AnyPtrExported {
     pub id: u32,
     pub content: Vec<u8>,
@@ -19,12 +21,12 @@ impl AnyPtrExported {
         [ret, self.content].concat()
     }
 
-    pub fn deserialize(b: &[u8]) -> anyhow::Result<Self> {
+    pub fn deserialize(b: &[u8]) -> Result<Self, Error> {
         if b.len() < 4 {
-            anyhow::bail!("any pointer to small")
+            return Err(Error::Truncated(b.len()));
         }
         Ok(Self {
-            id: u32::from_be_bytes(b[..4].try_into()?),
+            id: u32::from_be_bytes([b[0], b[1], b[2], b[3]]),
             content: b[4..].to_vec(),
         })
     }
@@ -33,6 +35,14 @@ impl AnyPtrExported {
 pub enum Type {
     String(Box<StringPtr>),
     Buffer(Box<BufferPtr>),
+    /// `Array<T>`, the element type is described in the runtime type information
+    Array(Box<AnyPtr>),
+    /// `StaticArray<T>`
+    StaticArray(Box<AnyPtr>),
+    /// Views over an ArrayBuffer such as `Uint8Array` or `Float64Array`
+    TypedArray(Box<AnyPtr>),
+    Map(Box<AnyPtr>),
+    Set(Box<AnyPtr>),
     Any(Box<AnyPtr>),
 }
 
@@ -41,59 +51,103 @@ impl Type {
         match self {
             Type::String(ptr) => ptr.offset(),
             Type::Buffer(ptr) => ptr.offset(),
-            Type::Any(ptr) => ptr.offset(),
+            Type::Array(ptr)
+            | Type::StaticArray(ptr)
+            | Type::TypedArray(ptr)
+            | Type::Map(ptr)
+            | Type::Set(ptr)
+            | Type::Any(ptr) => ptr.offset(),
         }
     }
 }
 
+impl Pointer for Type {
+    fn offset(&self) -> u32 {
+        Type::offset(self)
+    }
+}
+
 impl AnyPtr {
     pub fn new(offset: u32) -> Self {
-        Self(WasmPtr::new(offset))
+        Self(offset)
     }
-    pub fn to_type(self, memory: &Memory) -> anyhow::Result<Type> {
-        let t = ptr_id(self.offset(), memory)?;
+    /// Resolve the type of the object with its class id. Collections are
+    /// classified with the runtime type information if the environment
+    /// found `__rtti_base`, otherwise they are returned as `Type::Any`.
+    pub fn to_type<R: Runtime>(self, env: &R) -> Result<Type, Error> {
+        let t = ptr_id(self.offset(), env)?;
         if t == 0 {
-            Ok(Type::Buffer(Box::new(BufferPtr::new(self.offset()))))
+            return Ok(Type::Buffer(Box::new(BufferPtr::new(self.offset()))));
         } else if t == 1 {
-            Ok(Type::String(Box::new(StringPtr::new(self.offset()))))
+            return Ok(Type::String(Box::new(StringPtr::new(self.offset()))));
+        }
+        let info = match env.rtti() {
+            Ok(rtti) => rtti.type_info(t, env)?,
+            _ => return Ok(Type::Any(Box::new(self))),
+        };
+        if info.is_array() {
+            Ok(Type::Array(Box::new(self)))
+        } else if info.is_static_array() {
+            Ok(Type::StaticArray(Box::new(self)))
+        } else if info.is_map() {
+            Ok(Type::Map(Box::new(self)))
+        } else if info.is_set() {
+            Ok(Type::Set(Box::new(self)))
+        } else if info.is_array_buffer_view() {
+            Ok(Type::TypedArray(Box::new(self)))
         } else {
             Ok(Type::Any(Box::new(self)))
         }
     }
+    /// Class id stored in the object header
+    pub fn id<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<u32, Error> {
+        ptr_id(self.offset(), memory)
+    }
     /// Get ptr stored offset
     pub fn offset(&self) -> u32 {
-        self.0.offset()
+        self.0
     }
-    pub fn export(&self, memory: &Memory) -> anyhow::Result<AnyPtrExported> {
+    pub fn export<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<AnyPtrExported, Error> {
         let content = self.read(memory)?;
         let id = ptr_id(self.offset(), memory)?;
         Ok(AnyPtrExported { content, id })
     }
     /// Create a new pointer with an allocation and write the pointer that
     /// has been writen. Return a pointer type.
-    pub fn import(ptr_exported: &AnyPtrExported, env: &Env) -> anyhow::Result<Type> {
-        if ptr_exported.id == 0 {
-            Ok(Type::Buffer(BufferPtr::alloc(&ptr_exported.content, env)?))
+    pub fn import<R: Runtime>(
+        ptr_exported: &AnyPtrExported,
+        env: &R,
+    ) -> Result<Pinned<Type, R>, Error> {
+        let ptr = if ptr_exported.id == 0 {
+            let ptr = BufferPtr::alloc(&ptr_exported.content, env)?;
+            Type::Buffer(Box::new(ptr.leak()))
         } else if ptr_exported.id == 1 {
-            let utf16_vec = unsafe {
-                let len = ptr_exported.content.len();
-                if len % 2 != 0 {
-                    anyhow::bail!("Cannot cast u8 slice into u16")
-                }
-                let c = ptr_exported.content.as_ptr().cast::<u16>();
-                let a = std::slice::from_raw_parts(c, len / 2);
-                a.to_vec()
-            };
-            Ok(Type::String(StringPtr::alloc(
-                &String::from_utf16_lossy(&utf16_vec),
-                env,
-            )?))
+            let len = ptr_exported.content.len();
+            if len % 2 != 0 {
+                return Err(Error::InvalidUtf16 {
+                    offset: u32::try_from(len - 1)?,
+                });
+            }
+            let utf16_vec: Vec<u16> = ptr_exported
+                .content
+                .chunks_exact(2)
+                .map(|b| u16::from_le_bytes([b[0], b[1]]))
+                .collect();
+            let ptr = StringPtr::alloc(&String::from_utf16_lossy(&utf16_vec), env)?;
+            Type::String(Box::new(ptr.leak()))
         } else {
             // todo write type anyway
             let ptr = AnyPtr::alloc(&ptr_exported.content, env)?;
             set_id(ptr.offset(), ptr_exported.id, env)?;
-            Ok(Type::Any(ptr))
-        }
+            Type::Any(Box::new(ptr.leak()))
+        };
+        Ok(Pinned::new(ptr, env))
+    }
+}
+
+impl Pointer for AnyPtr {
+    fn offset(&self) -> u32 {
+        self.0
     }
 }
 
@@ -108,121 +162,78 @@ unsafe impl FromToNativeWasmType for AnyPtr {
 }
 
 impl Read<Vec<u8>> for AnyPtr {
-    fn read(&self, memory: &Memory) -> anyhow::Result<Vec<u8>> {
-        let size = self.size(memory)?;
-        if let Some(buf) = self.0.deref(memory, 0, size * 2) {
-            Ok(buf.iter().map(|b| b.get()).collect())
-        } else {
-            anyhow::bail!("Wrong offset: can't read any object")
-        }
+    fn read<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<Vec<u8>, Error> {
+        read_bytes(self.offset(), self.size(memory)? * 2, memory)
     }
 
-    fn size(&self, memory: &Memory) -> anyhow::Result<u32> {
-        size(self.0.offset(), memory)
+    fn size<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<u32, Error> {
+        size(self.0, memory)
     }
 }
 
 impl Write<Vec<u8>> for AnyPtr {
-    fn alloc(value: &Vec<u8>, env: &Env) -> anyhow::Result<Box<AnyPtr>> {
-        let new = export_asr!(fn_new, env);
-        let size = i32::try_from(value.len())?;
-        let offset = u32::try_from(
-            if let Some(value) = new.call(&[Value::I32(size), Value::I32(0)])?.get(0) {
-                match value.i32() {
-                    Some(offset) => offset,
-                    _ => anyhow::bail!("Unable to allocate value"),
-                }
-            } else {
-                anyhow::bail!("Unable to allocate value")
-            },
-        )?;
-        write_buffer(offset, value, env)?;
-        Ok(Box::new(AnyPtr::new(offset)))
-    }
-
-    fn write(&mut self, value: &Vec<u8>, env: &Env) -> anyhow::Result<Box<Self>> {
-        let memory = match env.memory.get_ref() {
-            Some(mem) => mem,
-            _ => anyhow::bail!("Cannot get memory"),
-        };
-        let prev_size = size(self.offset(), memory)?;
+    fn alloc<R: Runtime>(value: &Vec<u8>, env: &R) -> Result<Pinned<AnyPtr, R>, Error> {
+        let size = u32::try_from(value.len())?;
+        let offset = alloc(size, 0, env)?;
+        write_bytes(offset, value, env)?;
+
+        // pin
+        pin(offset, env)?;
+
+        Ok(Pinned::new(AnyPtr::new(offset), env))
+    }
+
+    fn write<R: Runtime>(&mut self, value: &Vec<u8>, env: &R) -> Result<Box<Self>, Error> {
+        let prev_size = byte_size(self.offset(), env)?;
         let new_size = u32::try_from(value.len())?;
         if prev_size == new_size {
-            write_buffer(self.offset(), value, env)?;
+            write_bytes(self.offset(), value, env)?;
             Ok(Box::new(*self))
         } else {
             // unpin old ptr
-            let unpin = export_asr!(fn_pin, env);
-            unpin.call(&[Value::I32(self.offset().try_into()?)])?;
+            unpin(self.offset(), env)?;
 
             // collect
-            let collect = export_asr!(fn_collect, env);
-            collect.call(&[])?;
+            collect(env)?;
 
             // alloc with new size
-            AnyPtr::alloc(value, env)
+            Ok(Box::new(AnyPtr::alloc(value, env)?.leak()))
         }
     }
 
-    fn free(self, _env: &Env) -> anyhow::Result<()> {
-        todo!("Release the memory from this string")
+    fn free<R: Runtime>(self, env: &R) -> Result<(), Error> {
+        release(self.offset(), env)
     }
 }
 
-fn write_buffer(offset: u32, value: &[u8], env: &Env) -> anyhow::Result<()> {
-    let view = match env.memory.get_ref() {
-        Some(mem) => mem.view::<u8>(),
-        _ => anyhow::bail!("Uninitialized memory"),
-    };
-    // We count in 32 so we have to devide by 2
-    let from = usize::try_from(offset)?;
-    for (bytes, cell) in value.iter().zip(view[from..from + value.len()].iter()) {
-        cell.set(*bytes);
-    }
-    Ok(())
+fn size<M: MemoryAccess + ?Sized>(offset: u32, memory: &M) -> Result<u32, Error> {
+    Ok(byte_size(offset, memory)? / 2)
 }
 
-fn size(offset: u32, memory: &Memory) -> anyhow::Result<u32> {
+/// Size of the object in bytes, `rtSize` in its header.
+fn byte_size<M: MemoryAccess + ?Sized>(offset: u32, memory: &M) -> Result<u32, Error> {
     if offset < 8 {
-        anyhow::bail!("Wrong offset: less than 8")
+        return Err(Error::out_of_bounds(offset, memory));
     }
     // read -4 offset
     // https://www.assemblyscript.org/memory.html#internals
-    if let Some(cell) = memory.view::<u32>().get(offset as usize / (32 / 8) - 1) {
-        Ok(cell.get() / 2)
-    } else {
-        anyhow::bail!("Wrong offset: can't read size")
-    }
+    read_u32(offset - 4, memory).map_err(|_| Error::out_of_bounds(offset, memory))
 }
 
-fn ptr_id(offset: u32, memory: &Memory) -> anyhow::Result<u32> {
+fn ptr_id<M: MemoryAccess + ?Sized>(offset: u32, memory: &M) -> Result<u32, Error> {
     if offset < 8 {
-        anyhow::bail!("Wrong offset: less than 8")
+        return Err(Error::out_of_bounds(offset, memory));
     }
     // read -8 offset
     // https://www.assemblyscript.org/memory.html#internals
-    if let Some(cell) = memory.view::<u32>().get(offset as usize / (32 / 8) - 2) {
-        Ok(cell.get())
-    } else {
-        anyhow::bail!("Wrong offset: can't read type")
-    }
+    read_u32(offset - 8, memory).map_err(|_| Error::out_of_bounds(offset, memory))
 }
 
-fn set_id(offset: u32, id: u32, env: &Env) -> anyhow::Result<()> {
+fn set_id<M: MemoryAccess + ?Sized>(offset: u32, id: u32, memory: &M) -> Result<(), Error> {
     if offset < 8 {
-        anyhow::bail!("Wrong offset: less than 8")
+        return Err(Error::out_of_bounds(offset, memory));
     }
-    let memory = match env.memory.get_ref() {
-        Some(mem) => mem,
-        _ => anyhow::bail!("Uninitialized memory"),
-    };
-    // read -8 offset
+    // write -8 offset
     // https://www.assemblyscript.org/memory.html#internals
-    if let Some(cell) = memory.view::<u32>().get((offset as usize / (32 / 8)) - 2) {
-        cell.set(id);
-    } else {
-        anyhow::bail!("Wrong offset: can't read type")
-    }
-
-    Ok(())
+    write_u32(offset - 8, id, memory).map_err(|_| Error::out_of_bounds(offset, memory))
 }
@@ -0,0 +1,133 @@
+use super::{Element, Error, MemoryAccess, Pinned, Pointer, Read, Runtime, Write};
+use crate::element::{byte_length, read_elements, write_elements};
+use crate::rtti::ARRAY;
+use crate::tools::{alloc, collect, pin, read_field, release, unpin, write_u32};
+
+use std::convert::TryFrom;
+use std::marker::PhantomData;
+use wasmer::FromToNativeWasmType;
+
+// Layout of `Array<T>`
+// https://www.assemblyscript.org/runtime.html#memory-layout
+const BUFFER: u32 = 0;
+const DATA_START: u32 = 4;
+const BYTE_LENGTH: u32 = 8;
+const LENGTH: u32 = 12;
+const ARRAY_SIZE: u32 = 16;
+
+/// Pointer to an AssemblyScript `Array<T>`.
+///
+/// `T` is either a number or a pointer to a managed object, for example an
+/// `Array<string>` is read as a `Vec<StringPtr>`, and an `Array<Array<i32>>`
+/// as a `Vec<ArrayPtr<i32>>`.
+pub struct ArrayPtr<T>(u32, PhantomData<T>);
+
+impl<T> ArrayPtr<T> {
+    pub fn new(offset: u32) -> Self {
+        Self(offset, PhantomData)
+    }
+    pub fn offset(&self) -> u32 {
+        self.0
+    }
+    /// Number of elements in the array
+    pub fn len<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<u32, Error> {
+        read_field(self.offset(), LENGTH, memory)
+    }
+    pub fn is_empty<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<bool, Error> {
+        Ok(self.len(memory)? == 0)
+    }
+}
+
+impl<T> Clone for ArrayPtr<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ArrayPtr<T> {}
+
+impl<T> Pointer for ArrayPtr<T> {
+    fn offset(&self) -> u32 {
+        self.0
+    }
+}
+
+unsafe impl<T> FromToNativeWasmType for ArrayPtr<T> {
+    type Native = i32;
+    fn to_native(self) -> Self::Native {
+        self.offset() as i32
+    }
+    fn from_native(n: Self::Native) -> Self {
+        Self::new(n as u32)
+    }
+}
+
+impl<T> Element for ArrayPtr<T> {
+    const ALIGN: u32 = 2;
+    const MANAGED: bool = true;
+
+    fn from_le_bytes(bytes: &[u8]) -> Self {
+        Self::new(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+    fn write_le_bytes(&self, bytes: &mut [u8]) {
+        bytes.copy_from_slice(&self.offset().to_le_bytes())
+    }
+}
+
+impl<T: Element> Read<Vec<T>> for ArrayPtr<T> {
+    fn read<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<Vec<T>, Error> {
+        let data_start = read_field(self.offset(), DATA_START, memory)?;
+        let len = self.len(memory)?;
+        read_elements(data_start, len, memory)
+    }
+
+    /// Size of the array data in bytes.
+    fn size<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<u32, Error> {
+        read_field(self.offset(), BYTE_LENGTH, memory)
+    }
+}
+
+impl<T: Element> Write<Vec<T>> for ArrayPtr<T> {
+    fn alloc<R: Runtime>(value: &Vec<T>, env: &R) -> Result<Pinned<ArrayPtr<T>, R>, Error> {
+        let id = env.rtti()?.find_collection::<T, _>(ARRAY, env)?;
+        let byte_length = byte_length::<T>(value.len())?;
+
+        // The backing buffer isn't referenced yet, pin it while the array
+        // is allocated in case a collection happens.
+        let buffer = alloc(byte_length, 0, env)?;
+        write_elements(buffer, value, env)?;
+        pin(buffer, env)?;
+        let offset = alloc(ARRAY_SIZE, id, env);
+        unpin(buffer, env)?;
+        let offset = offset?;
+
+        write_u32(offset + BUFFER, buffer, env)?;
+        write_u32(offset + DATA_START, buffer, env)?;
+        write_u32(offset + BYTE_LENGTH, byte_length, env)?;
+        write_u32(offset + LENGTH, u32::try_from(value.len())?, env)?;
+
+        pin(offset, env)?;
+        Ok(Pinned::new(ArrayPtr::new(offset), env))
+    }
+
+    fn write<R: Runtime>(&mut self, value: &Vec<T>, env: &R) -> Result<Box<ArrayPtr<T>>, Error> {
+        if self.len(env)? == u32::try_from(value.len())? {
+            let data_start = read_field(self.offset(), DATA_START, env)?;
+            write_elements(data_start, value, env)?;
+            Ok(Box::new(*self))
+        } else {
+            // unpin old ptr
+            unpin(self.offset(), env)?;
+
+            // collect
+            collect(env)?;
+
+            // alloc with new size
+            Ok(Box::new(ArrayPtr::alloc(value, env)?.leak()))
+        }
+    }
+
+    fn free<R: Runtime>(self, env: &R) -> Result<(), Error> {
+        release(self.offset(), env)
+    }
+}
@@ -1,18 +1,24 @@
-use super::{Env, Memory, Read, Write};
-use std::convert::{TryFrom, TryInto};
-use wasmer::{Array, FromToNativeWasmType, Value, WasmCell, WasmPtr};
+use super::{Error, MemoryAccess, Pinned, Pointer, Read, Runtime, Write};
+use std::convert::TryFrom;
+use wasmer::FromToNativeWasmType;
 
-use crate::tools::export_asr;
+use crate::tools::{alloc, collect, pin, read_bytes, read_u32, release, unpin, write_bytes};
 
 #[derive(Clone, Copy)]
-pub struct BufferPtr(WasmPtr<u8, Array>);
+pub struct BufferPtr(u32);
 
 impl BufferPtr {
     pub fn new(offset: u32) -> Self {
-        Self(WasmPtr::new(offset))
+        Self(offset)
     }
     pub fn offset(&self) -> u32 {
-        self.0.offset()
+        self.0
+    }
+}
+
+impl Pointer for BufferPtr {
+    fn offset(&self) -> u32 {
+        self.0
     }
 }
 
@@ -27,100 +33,55 @@ unsafe impl FromToNativeWasmType for BufferPtr {
 }
 
 impl Read<Vec<u8>> for BufferPtr {
-    fn read(&self, memory: &Memory) -> anyhow::Result<Vec<u8>> {
-        let size = self.size(memory)?;
-        if let Some(buf) = self.0.deref(memory, 0, size) {
-            Ok(buf.iter().map(WasmCell::get).collect())
-        } else {
-            anyhow::bail!("Wrong offset: can't read buf")
-        }
+    fn read<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<Vec<u8>, Error> {
+        read_bytes(self.offset(), self.size(memory)?, memory)
     }
 
-    fn size(&self, memory: &Memory) -> anyhow::Result<u32> {
-        size(self.0.offset(), memory)
+    fn size<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<u32, Error> {
+        size(self.0, memory)
     }
 }
 
 impl Write<Vec<u8>> for BufferPtr {
-    fn alloc(value: &Vec<u8>, env: &Env) -> anyhow::Result<Box<BufferPtr>> {
-        let new = export_asr!(fn_new, env);
-        let size = i32::try_from(value.len())?;
+    fn alloc<R: Runtime>(value: &Vec<u8>, env: &R) -> Result<Pinned<BufferPtr, R>, Error> {
+        let size = u32::try_from(value.len())?;
+        let offset = alloc(size, 0, env)?;
+        write_bytes(offset, value, env)?;
+
+        // pin
+        pin(offset, env)?;
 
-        let offset = u32::try_from(
-            if let Some(value) = new.call(&[Value::I32(size), Value::I32(0)])?.get(0) {
-                match value.i32() {
-                    Some(offset) => offset,
-                    _ => anyhow::bail!("Unable to allocate value"),
-                }
-            } else {
-                anyhow::bail!("Unable to allocate value")
-            },
-        )?;
-        write_buffer(offset, value, env)?;
-        Ok(Box::new(BufferPtr::new(offset)))
+        Ok(Pinned::new(BufferPtr::new(offset), env))
     }
 
-    fn write(&mut self, value: &Vec<u8>, env: &Env) -> anyhow::Result<Box<Self>> {
-        let memory = match env.memory.get_ref() {
-            Some(mem) => mem,
-            _ => anyhow::bail!("Cannot get memory"),
-        };
-        let prev_size = size(self.offset(), memory)?;
+    fn write<R: Runtime>(&mut self, value: &Vec<u8>, env: &R) -> Result<Box<Self>, Error> {
+        let prev_size = size(self.offset(), env)?;
         let new_size = u32::try_from(value.len())?;
         if prev_size == new_size {
-            write_buffer(self.offset(), value, env)?;
+            write_bytes(self.offset(), value, env)?;
             Ok(Box::new(*self))
         } else {
             // unpin old ptr
-            let unpin = export_asr!(fn_pin, env);
-            unpin.call(&[Value::I32(self.offset().try_into()?)])?;
+            unpin(self.offset(), env)?;
 
             // collect
-            let collect = export_asr!(fn_collect, env);
-            collect.call(&[])?;
+            collect(env)?;
 
             // alloc with new size
-            BufferPtr::alloc(value, env)
+            Ok(Box::new(BufferPtr::alloc(value, env)?.leak()))
         }
     }
 
-    fn free(self, _env: &Env) -> anyhow::Result<()> {
-        todo!("Release the memory from this string")
-    }
-}
-
-fn write_buffer(offset: u32, value: &[u8], env: &Env) -> anyhow::Result<()> {
-    let view = match env.memory.get_ref() {
-        Some(mem) => mem.view::<u8>(),
-        _ => anyhow::bail!("Uninitialized memory"),
-    };
-
-    if cfg!(feature = "no_thread") {
-        let subarray_view = view.subarray(offset, offset + (value.len() as u32));
-        // copy_from is unsafe because the caller will need to make sure there are no data races
-        // when copying memory into the view.
-        unsafe {
-            subarray_view.copy_from(value);
-        }
-    } else {
-        let from = usize::try_from(offset)?;
-        for (bytes, cell) in value.iter().zip(view[from..from + value.len()].iter()) {
-            cell.set(*bytes);
-        }
+    fn free<R: Runtime>(self, env: &R) -> Result<(), Error> {
+        release(self.offset(), env)
     }
-
-    Ok(())
 }
 
-fn size(offset: u32, memory: &Memory) -> anyhow::Result<u32> {
+fn size<M: MemoryAccess + ?Sized>(offset: u32, memory: &M) -> Result<u32, Error> {
     if offset < 4 {
-        anyhow::bail!("Wrong offset: less than 2")
+        return Err(Error::out_of_bounds(offset, memory));
     }
     // read -4 offset
     // https://www.assemblyscript.org/runtime.html#memory-layout
-    if let Some(cell) = memory.view::<u32>().get(offset as usize / (32 / 8) - 1) {
-        Ok(cell.get())
-    } else {
-        anyhow::bail!("Wrong offset: can't read size")
-    }
+    read_u32(offset - 4, memory).map_err(|_| Error::out_of_bounds(offset, memory))
 }
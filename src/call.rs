@@ -0,0 +1,221 @@
+use super::{
+    AnyPtr, BufferPtr, Error, MemoryAccess, Pinned, Pointer, Read, Runtime, StringPtr, Write,
+};
+use wasmer::{FromToNativeWasmType, Type as ValType, Value};
+
+/// A Rust value given to an export by `EnvMut::call`.
+pub trait Arg {
+    /// Wasm type of the value.
+    const TYPE: ValType;
+
+    /// Convert the value to a wasm value. Strings and buffers are allocated
+    /// in the guest, their guards are pushed in `pinned` and dropped once the
+    /// call returns.
+    fn to_value<R: Runtime>(
+        &self,
+        env: &R,
+        pinned: &mut Vec<Pinned<AnyPtr, R>>,
+    ) -> Result<Value, Error>;
+}
+
+/// Arguments of `EnvMut::call`, a tuple of `Arg`.
+pub trait Args {
+    fn to_values<R: Runtime>(
+        &self,
+        env: &R,
+        pinned: &mut Vec<Pinned<AnyPtr, R>>,
+    ) -> Result<Vec<Value>, Error>;
+}
+
+/// Result of an export called by `EnvMut::call`, read back from its wasm
+/// results.
+pub trait Return: Sized {
+    /// Wasm types of the results.
+    const TYPES: &'static [ValType];
+
+    fn from_values<M: MemoryAccess + ?Sized>(values: &[Value], memory: &M) -> Result<Self, Error>;
+}
+
+/// Keep the guard of an argument until the end of the call.
+fn guard<P: Pointer, R: Runtime>(
+    ptr: Pinned<P, R>,
+    env: &R,
+    pinned: &mut Vec<Pinned<AnyPtr, R>>,
+) -> Value {
+    let offset = ptr.leak().offset();
+    pinned.push(Pinned::new(AnyPtr::new(offset), env));
+    Value::I32(offset as i32)
+}
+
+impl Arg for &str {
+    const TYPE: ValType = ValType::I32;
+
+    fn to_value<R: Runtime>(
+        &self,
+        env: &R,
+        pinned: &mut Vec<Pinned<AnyPtr, R>>,
+    ) -> Result<Value, Error> {
+        Ok(guard(
+            StringPtr::alloc(&self.to_string(), env)?,
+            env,
+            pinned,
+        ))
+    }
+}
+
+impl Arg for String {
+    const TYPE: ValType = ValType::I32;
+
+    fn to_value<R: Runtime>(
+        &self,
+        env: &R,
+        pinned: &mut Vec<Pinned<AnyPtr, R>>,
+    ) -> Result<Value, Error> {
+        Ok(guard(StringPtr::alloc(self, env)?, env, pinned))
+    }
+}
+
+/// Bytes are passed as an `ArrayBuffer`.
+impl Arg for &[u8] {
+    const TYPE: ValType = ValType::I32;
+
+    fn to_value<R: Runtime>(
+        &self,
+        env: &R,
+        pinned: &mut Vec<Pinned<AnyPtr, R>>,
+    ) -> Result<Value, Error> {
+        Ok(guard(BufferPtr::alloc(&self.to_vec(), env)?, env, pinned))
+    }
+}
+
+impl Arg for Vec<u8> {
+    const TYPE: ValType = ValType::I32;
+
+    fn to_value<R: Runtime>(
+        &self,
+        env: &R,
+        pinned: &mut Vec<Pinned<AnyPtr, R>>,
+    ) -> Result<Value, Error> {
+        Ok(guard(BufferPtr::alloc(self, env)?, env, pinned))
+    }
+}
+
+/// Objects already in the guest are passed as is.
+impl<P: Pointer> Arg for P {
+    const TYPE: ValType = ValType::I32;
+
+    fn to_value<R: Runtime>(&self, _: &R, _: &mut Vec<Pinned<AnyPtr, R>>) -> Result<Value, Error> {
+        Ok(Value::I32(self.offset() as i32))
+    }
+}
+
+macro_rules! primitive {
+    ($($type:ty => $variant:ident as $native:ty),*) => {
+        $(
+            impl Arg for $type {
+                const TYPE: ValType = ValType::$variant;
+
+                fn to_value<R: Runtime>(&self, _: &R, _: &mut Vec<Pinned<AnyPtr, R>>) -> Result<Value, Error> {
+                    Ok(Value::$variant(*self as $native))
+                }
+            }
+
+            impl Return for $type {
+                const TYPES: &'static [ValType] = &[ValType::$variant];
+
+                fn from_values<M: MemoryAccess + ?Sized>(values: &[Value], _: &M) -> Result<Self, Error> {
+                    match values {
+                        [Value::$variant(value)] => Ok(*value as $type),
+                        _ => Err(Error::Signature),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+primitive!(
+    i32 => I32 as i32,
+    u32 => I32 as i32,
+    i64 => I64 as i64,
+    u64 => I64 as i64,
+    f32 => F32 as f32,
+    f64 => F64 as f64
+);
+
+impl Arg for bool {
+    const TYPE: ValType = ValType::I32;
+
+    fn to_value<R: Runtime>(&self, _: &R, _: &mut Vec<Pinned<AnyPtr, R>>) -> Result<Value, Error> {
+        Ok(Value::I32(*self as i32))
+    }
+}
+
+impl Return for bool {
+    const TYPES: &'static [ValType] = &[ValType::I32];
+
+    fn from_values<M: MemoryAccess + ?Sized>(values: &[Value], memory: &M) -> Result<Self, Error> {
+        Ok(i32::from_values(values, memory)? != 0)
+    }
+}
+
+impl Return for () {
+    const TYPES: &'static [ValType] = &[];
+
+    fn from_values<M: MemoryAccess + ?Sized>(values: &[Value], _: &M) -> Result<Self, Error> {
+        match values {
+            [] => Ok(()),
+            _ => Err(Error::Signature),
+        }
+    }
+}
+
+impl Return for String {
+    const TYPES: &'static [ValType] = &[ValType::I32];
+
+    fn from_values<M: MemoryAccess + ?Sized>(values: &[Value], memory: &M) -> Result<Self, Error> {
+        StringPtr::from_values(values, memory)?.read(memory)
+    }
+}
+
+/// The `ArrayBuffer` is read as bytes.
+impl Return for Vec<u8> {
+    const TYPES: &'static [ValType] = &[ValType::I32];
+
+    fn from_values<M: MemoryAccess + ?Sized>(values: &[Value], memory: &M) -> Result<Self, Error> {
+        BufferPtr::from_values(values, memory)?.read(memory)
+    }
+}
+
+/// The object isn't pinned, it can be collected by the next allocation.
+impl<P: Pointer + FromToNativeWasmType<Native = i32>> Return for P {
+    const TYPES: &'static [ValType] = &[ValType::I32];
+
+    fn from_values<M: MemoryAccess + ?Sized>(values: &[Value], memory: &M) -> Result<Self, Error> {
+        Ok(P::from_native(i32::from_values(values, memory)?))
+    }
+}
+
+macro_rules! args {
+    ($($arg:ident),*) => {
+        impl<$($arg: Arg),*> Args for ($($arg,)*) {
+            #[allow(non_snake_case, unused_variables)]
+            fn to_values<R: Runtime>(
+                &self,
+                env: &R,
+                pinned: &mut Vec<Pinned<AnyPtr, R>>,
+            ) -> Result<Vec<Value>, Error> {
+                let ($($arg,)*) = self;
+                Ok(vec![$($arg.to_value(env, pinned)?),*])
+            }
+        }
+    };
+}
+
+args!();
+args!(A);
+args!(A, B);
+args!(A, B, C);
+args!(A, B, C, D);
+args!(A, B, C, D, E);
+args!(A, B, C, D, E, F);
@@ -0,0 +1,164 @@
+use super::{Element, Error, MemoryAccess, Pinned, Pointer, Read, Runtime, Write};
+use crate::element::{read_element, write_elements};
+use crate::tools::{alloc, pin, read_u32, release};
+
+use std::marker::PhantomData;
+use wasmer::FromToNativeWasmType;
+
+/// A Rust struct mapped on the fields of an AssemblyScript class.
+///
+/// It's usually implemented with `#[derive(AsClass)]`, which reads and writes
+/// the fields in declaration order with `ClassFields`.
+pub trait AsClass: Sized {
+    /// Class id of the instances, as given by `idof<T>()` in the guest. It's
+    /// only needed to allocate instances.
+    const ID: Option<u32>;
+    /// Name of the class, for error messages.
+    const NAME: &'static str;
+
+    /// Size of an instance in bytes.
+    fn size() -> u32;
+    fn read_fields<M: MemoryAccess + ?Sized>(
+        fields: &mut ClassFields,
+        memory: &M,
+    ) -> Result<Self, Error>;
+    fn write_fields<M: MemoryAccess + ?Sized>(
+        &self,
+        fields: &mut ClassFields,
+        memory: &M,
+    ) -> Result<(), Error>;
+}
+
+/// Cursor over the fields of a class instance.
+///
+/// AssemblyScript lays the fields out in declaration order, each one aligned
+/// on its own size: a `u8` followed by an `i32` leaves 3 bytes of padding.
+pub struct ClassFields {
+    offset: u32,
+    end: u32,
+}
+
+impl ClassFields {
+    /// Start before the first field of the instance at `offset`.
+    pub fn new(offset: u32) -> Self {
+        Self { offset, end: 0 }
+    }
+
+    /// Offset in memory of the next field, of type `T`.
+    pub fn field<T: Element>(&mut self) -> u32 {
+        let field = (self.end + T::size() - 1) & !(T::size() - 1);
+        self.end = field + T::size();
+        self.offset + field
+    }
+
+    pub fn read<T: Element, M: MemoryAccess + ?Sized>(&mut self, memory: &M) -> Result<T, Error> {
+        read_element(self.field::<T>(), memory)
+    }
+
+    pub fn write<T: Element, M: MemoryAccess + ?Sized>(
+        &mut self,
+        value: &T,
+        memory: &M,
+    ) -> Result<(), Error> {
+        write_elements(self.field::<T>(), std::slice::from_ref(value), memory)
+    }
+
+    /// Size in bytes of the fields seen so far.
+    pub fn size(&self) -> u32 {
+        self.end
+    }
+}
+
+/// Pointer to an instance of an AssemblyScript class mapped on `T`.
+///
+/// ```ignore
+/// #[derive(AsClass)]
+/// #[as_class(id = 3)]
+/// struct MyStruct {
+///     v: i32,
+///     a: i32,
+/// }
+///
+/// let ptr: MyStructPtr = get_struct.call()?;
+/// let my_struct: MyStruct = ptr.read(memory)?;
+/// ```
+pub struct ClassPtr<T>(u32, PhantomData<T>);
+
+impl<T> ClassPtr<T> {
+    pub fn new(offset: u32) -> Self {
+        Self(offset, PhantomData)
+    }
+    pub fn offset(&self) -> u32 {
+        self.0
+    }
+}
+
+impl<T> Clone for ClassPtr<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ClassPtr<T> {}
+
+impl<T> Pointer for ClassPtr<T> {
+    fn offset(&self) -> u32 {
+        self.0
+    }
+}
+
+unsafe impl<T> FromToNativeWasmType for ClassPtr<T> {
+    type Native = i32;
+    fn to_native(self) -> Self::Native {
+        self.offset() as i32
+    }
+    fn from_native(n: Self::Native) -> Self {
+        Self::new(n as u32)
+    }
+}
+
+impl<T> Element for ClassPtr<T> {
+    const ALIGN: u32 = 2;
+    const MANAGED: bool = true;
+
+    fn from_le_bytes(bytes: &[u8]) -> Self {
+        Self::new(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+    fn write_le_bytes(&self, bytes: &mut [u8]) {
+        bytes.copy_from_slice(&self.offset().to_le_bytes())
+    }
+}
+
+impl<T: AsClass> Read<T> for ClassPtr<T> {
+    fn read<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<T, Error> {
+        T::read_fields(&mut ClassFields::new(self.offset()), memory)
+    }
+
+    fn size<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<u32, Error> {
+        if self.offset() < 4 {
+            return Err(Error::out_of_bounds(self.offset(), memory));
+        }
+        read_u32(self.offset() - 4, memory)
+    }
+}
+
+impl<T: AsClass> Write<T> for ClassPtr<T> {
+    fn alloc<R: Runtime>(value: &T, env: &R) -> Result<Pinned<ClassPtr<T>, R>, Error> {
+        let id = T::ID.ok_or(Error::MissingClassId(T::NAME))?;
+        let offset = alloc(T::size(), id, env)?;
+        value.write_fields(&mut ClassFields::new(offset), env)?;
+
+        pin(offset, env)?;
+        Ok(Pinned::new(ClassPtr::new(offset), env))
+    }
+
+    /// Instances have a fixed size, the fields are always written in place.
+    fn write<R: Runtime>(&mut self, value: &T, env: &R) -> Result<Box<ClassPtr<T>>, Error> {
+        value.write_fields(&mut ClassFields::new(self.offset()), env)?;
+        Ok(Box::new(*self))
+    }
+
+    fn free<R: Runtime>(self, env: &R) -> Result<(), Error> {
+        release(self.offset(), env)
+    }
+}
@@ -0,0 +1,160 @@
+use crate::tools::{read_bytes, write_bytes};
+use crate::{AnyPtr, BufferPtr, Error, MemoryAccess, StringPtr};
+use std::convert::TryFrom;
+
+/// A value that can be stored in an AssemblyScript array or collection.
+///
+/// Numbers are stored as is, managed objects (strings, arrays...) are stored
+/// as a pointer to the object. The flags match the runtime type information
+/// emitted by the compiler, so the right class id can be found when the host
+/// allocates a collection.
+pub trait Element: Sized {
+    /// Log2 of the size of the value in bytes.
+    const ALIGN: u32;
+    const SIGNED: bool = false;
+    const FLOAT: bool = false;
+    /// The value is a pointer to a managed object.
+    const MANAGED: bool = false;
+    /// The value is a reference that can be null.
+    const NULLABLE: bool = false;
+
+    /// Size of the value in bytes.
+    fn size() -> u32 {
+        1 << Self::ALIGN
+    }
+    /// Decode the value from `bytes`, which are `Self::size()` little endian
+    /// bytes.
+    fn from_le_bytes(bytes: &[u8]) -> Self;
+    /// Encode the value into `bytes`, which are `Self::size()` long.
+    fn write_le_bytes(&self, bytes: &mut [u8]);
+}
+
+macro_rules! impl_number {
+    ($type:ty, $align:expr, $signed:expr, $float:expr) => {
+        impl Element for $type {
+            const ALIGN: u32 = $align;
+            const SIGNED: bool = $signed;
+            const FLOAT: bool = $float;
+
+            fn from_le_bytes(bytes: &[u8]) -> Self {
+                let mut buf = [0u8; 1 << $align];
+                buf.copy_from_slice(bytes);
+                <$type>::from_le_bytes(buf)
+            }
+            fn write_le_bytes(&self, bytes: &mut [u8]) {
+                bytes.copy_from_slice(&self.to_le_bytes())
+            }
+        }
+    };
+}
+
+impl_number!(u8, 0, false, false);
+impl_number!(i8, 0, true, false);
+impl_number!(u16, 1, false, false);
+impl_number!(i16, 1, true, false);
+impl_number!(u32, 2, false, false);
+impl_number!(i32, 2, true, false);
+impl_number!(u64, 3, false, false);
+impl_number!(i64, 3, true, false);
+impl_number!(f32, 2, true, true);
+impl_number!(f64, 3, true, true);
+
+impl Element for bool {
+    const ALIGN: u32 = 0;
+
+    fn from_le_bytes(bytes: &[u8]) -> Self {
+        bytes[0] != 0
+    }
+    fn write_le_bytes(&self, bytes: &mut [u8]) {
+        bytes[0] = *self as u8
+    }
+}
+
+macro_rules! impl_managed {
+    ($type:ty) => {
+        impl Element for $type {
+            const ALIGN: u32 = 2;
+            const MANAGED: bool = true;
+
+            fn from_le_bytes(bytes: &[u8]) -> Self {
+                <$type>::new(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            }
+            fn write_le_bytes(&self, bytes: &mut [u8]) {
+                bytes.copy_from_slice(&self.offset().to_le_bytes())
+            }
+        }
+    };
+}
+
+impl_managed!(StringPtr);
+impl_managed!(BufferPtr);
+impl_managed!(AnyPtr);
+
+/// A nullable reference, null is read as `None`. `T` must be a managed type,
+/// like `StringPtr` for a `string | null`.
+impl<T: Element> Element for Option<T> {
+    const ALIGN: u32 = 2;
+    const MANAGED: bool = true;
+    const NULLABLE: bool = true;
+
+    fn from_le_bytes(bytes: &[u8]) -> Self {
+        if bytes.iter().all(|byte| *byte == 0) {
+            None
+        } else {
+            Some(T::from_le_bytes(bytes))
+        }
+    }
+    fn write_le_bytes(&self, bytes: &mut [u8]) {
+        match self {
+            Some(value) => value.write_le_bytes(bytes),
+            None => bytes.fill(0),
+        }
+    }
+}
+
+/// Read a single element stored at `offset`.
+pub(crate) fn read_element<T: Element, M: MemoryAccess + ?Sized>(
+    offset: u32,
+    memory: &M,
+) -> Result<T, Error> {
+    Ok(T::from_le_bytes(&read_bytes(offset, T::size(), memory)?))
+}
+
+/// Read `len` elements stored from `offset`.
+pub(crate) fn read_elements<T: Element, M: MemoryAccess + ?Sized>(
+    offset: u32,
+    len: u32,
+    memory: &M,
+) -> Result<Vec<T>, Error> {
+    let size = len
+        .checked_mul(T::size())
+        .ok_or_else(|| Error::out_of_bounds(offset, memory))?;
+    let bytes = read_bytes(offset, size, memory)?;
+    Ok(bytes
+        .chunks_exact(usize::try_from(T::size())?)
+        .map(T::from_le_bytes)
+        .collect())
+}
+
+/// Size in bytes of `len` elements, to allocate their buffer.
+pub(crate) fn byte_length<T: Element>(len: usize) -> Result<u32, Error> {
+    u32::try_from(len)?
+        .checked_mul(T::size())
+        .ok_or(Error::Alloc { size: u32::MAX })
+}
+
+/// Write the elements of `value` from `offset`.
+pub(crate) fn write_elements<T: Element, M: MemoryAccess + ?Sized>(
+    offset: u32,
+    value: &[T],
+    memory: &M,
+) -> Result<(), Error> {
+    let mut bytes = vec![0u8; value.len() << T::ALIGN];
+    for (element, chunk) in value
+        .iter()
+        .zip(bytes.chunks_exact_mut(usize::try_from(T::size())?))
+    {
+        element.write_le_bytes(chunk);
+    }
+    write_bytes(offset, &bytes, memory)
+}
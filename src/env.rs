@@ -1,57 +1,347 @@
-use wasmer::{Function, HostEnvInitError, Instance, LazyInit, Memory, WasmerEnv};
+use crate::{
+    AbortError, AbortHandler, Args, Error, MemoryAccess, Return, Rtti, Runtime, Utf16Policy,
+};
+use std::cell::{Ref, RefCell, RefMut};
+use std::collections::HashSet;
+use std::rc::Rc;
+use std::sync::Arc;
+use wasmer::{
+    AsStoreMut, Exports, Extern, Function, FunctionEnvMut, Instance, Memory, RuntimeError, Value,
+};
 
+/// State of an instance, kept in the store by a `FunctionEnv<Env>` and given
+/// to the imports. It's accessed through an `EnvMut`.
 #[derive(Clone, Default)]
 pub struct Env {
-    pub memory: LazyInit<Memory>,
+    pub memory: Option<Memory>,
     pub fn_new: Option<Function>,
     pub fn_pin: Option<Function>,
     pub fn_unpin: Option<Function>,
     pub fn_collect: Option<Function>,
+    /// Functions exported by the instance, see `EnvMut::call`
+    pub exports: Exports,
+    /// Offset of the runtime type information, read from `__rtti_base`
+    pub rtti_base: Option<u32>,
+    /// Receives the messages of the `trace` import, see `Env::with_trace`
+    pub trace: Option<TraceSink>,
+    /// Seed of `Math.random`, see `Env::with_seed`
+    pub seed: Option<f64>,
+    /// What the `abort` import does, trap by default
+    pub on_abort: AbortHandler,
+    /// How strings with unpaired surrogates are read, see `Env::with_utf16`
+    pub utf16: Utf16Policy,
+    last_abort: Option<AbortError>,
+    /// Objects pinned by the host, see `Runtime::pinned`
+    pinned: HashSet<u32>,
 }
 
+/// Receives the message and the arguments given to `trace` by the guest.
+pub type TraceSink = Arc<dyn Fn(&str, &[f64]) + Send + Sync>;
+
 impl Env {
     pub fn new(
-        arg_memory: Memory,
+        memory: Memory,
         fn_new: Option<Function>,
         fn_pin: Option<Function>,
         fn_unpin: Option<Function>,
         fn_collect: Option<Function>,
     ) -> Env {
-        let mut memory = LazyInit::<Memory>::default();
-        memory.initialize(arg_memory);
         Env {
-            memory,
+            memory: Some(memory),
             fn_new,
             fn_pin,
             fn_unpin,
             fn_collect,
+            ..Env::default()
+        }
+    }
+
+    /// Send the messages of `trace` to `sink` instead of stderr.
+    pub fn with_trace(mut self, sink: impl Fn(&str, &[f64]) + Send + Sync + 'static) -> Self {
+        self.trace = Some(Arc::new(sink));
+        self
+    }
+
+    /// Handle the calls to `abort` with `handler` instead of trapping.
+    pub fn with_abort(mut self, handler: AbortHandler) -> Self {
+        self.on_abort = handler;
+        self
+    }
+
+    /// Seed `Math.random` with `seed` instead of the current time, so the
+    /// guest draws the same numbers on every run.
+    pub fn with_seed(mut self, seed: f64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Read the strings containing unpaired surrogates according to
+    /// `policy`, instead of replacing the surrogates with `U+FFFD`. It applies
+    /// to the strings read from the `EnvMut`, given by the guest to
+    /// `EnvMut::call` and to host functions included.
+    pub fn with_utf16(mut self, policy: Utf16Policy) -> Self {
+        self.utf16 = policy;
+        self
+    }
+
+    /// Get the memory and the exports of `instance`, created in `store`.
+    pub fn init(&mut self, instance: &Instance, store: &mut impl AsStoreMut) -> Result<(), Error> {
+        let memory = instance
+            .exports
+            .get_memory("memory")
+            .map_err(|_| Error::MissingExport("memory"))?;
+        self.memory = Some(memory.clone());
+        self.fn_new = instance.exports.get_function("__new").ok().cloned();
+        self.fn_pin = instance.exports.get_function("__pin").ok().cloned();
+        self.fn_unpin = instance.exports.get_function("__unpin").ok().cloned();
+        self.fn_collect = instance.exports.get_function("__collect").ok().cloned();
+        self.exports = instance
+            .exports
+            .iter()
+            .filter(|(_, export)| matches!(export, Extern::Function(_)))
+            .map(|(name, export)| (name.clone(), export.clone()))
+            .collect();
+        if let Ok(global) = instance.exports.get_global("__rtti_base") {
+            if let Value::I32(base) = global.get(store) {
+                self.rtti_base = Some(base as u32)
+            }
         }
+        Ok(())
+    }
+
+    /// Last call to `abort` by the guest, since the instance was created or
+    /// since the last call through `EnvMut::catch`.
+    pub fn last_abort(&self) -> Option<AbortError> {
+        self.last_abort.clone()
+    }
+
+    pub(crate) fn set_last_abort(&mut self, abort: AbortError) {
+        self.last_abort = Some(abort);
     }
 
-    pub fn init(&mut self, instance: &Instance) -> anyhow::Result<()> {
-        Ok(self.init_with_instance(instance)?)
+    /// Get the runtime type information table of the instance. The module
+    /// must be compiled with `--exportRuntime` (or export `__rtti_base`).
+    pub fn rtti(&self) -> Result<Rtti, Error> {
+        match self.rtti_base {
+            Some(base) => Ok(Rtti::new(base)),
+            _ => Err(Error::MissingExport("__rtti_base")),
+        }
     }
 }
 
-impl WasmerEnv for Env {
-    fn init_with_instance(&mut self, instance: &Instance) -> Result<(), HostEnvInitError> {
-        let mem: Memory = instance
+/// Access to the `Env` of an instance running with wasmer, and to its store.
+///
+/// wasmer needs the store to access the memory or call a function, so the
+/// handle borrows it until it's dropped. It's available in the meantime with
+/// `EnvMut::store`. Host functions wrap the `FunctionEnvMut` they're given
+/// the same way.
+///
+/// ```ignore
+/// let env = FunctionEnv::new(&mut store, Env::default());
+/// let import_object = imports(&mut store, &env);
+/// let instance = Instance::new(&mut store, &module, &import_object)?;
+/// let env = EnvMut::new(env.into_mut(&mut store));
+/// env.init(&instance)?;
+///
+/// let str_ptr = StringPtr::alloc(&"hello".to_string(), &env)?;
+/// assert_eq!(str_ptr.read(&env)?, "hello");
+/// ```
+#[derive(Clone)]
+pub struct EnvMut<'a> {
+    env: Rc<RefCell<FunctionEnvMut<'a, Env>>>,
+}
+
+impl<'a> EnvMut<'a> {
+    pub fn new(env: FunctionEnvMut<'a, Env>) -> Self {
+        Self {
+            env: Rc::new(RefCell::new(env)),
+        }
+    }
+
+    /// Get the memory and the exports of `instance`, see `Env::init`.
+    pub fn init(&self, instance: &Instance) -> Result<(), Error> {
+        let mut env = self.borrow_mut()?;
+        let (data, mut store) = env.data_and_store_mut();
+        data.init(instance, &mut store)
+    }
+
+    /// Borrow the store, to call the exports of the instance for example.
+    /// The environment can't be used until the borrow is released.
+    pub fn store(&self) -> RefMut<'_, FunctionEnvMut<'a, Env>> {
+        self.env.borrow_mut()
+    }
+
+    pub fn data(&self) -> Ref<'_, Env> {
+        Ref::map(self.env.borrow(), |env| env.data())
+    }
+
+    pub fn data_mut(&self) -> RefMut<'_, Env> {
+        RefMut::map(self.env.borrow_mut(), |env| env.data_mut())
+    }
+
+    /// Last call to `abort` by the guest, see `Env::last_abort`.
+    pub fn last_abort(&self) -> Option<AbortError> {
+        self.data().last_abort()
+    }
+
+    /// Call the export `name` with Rust values. Strings and bytes are
+    /// allocated in the guest and pinned until the call returns, even if it
+    /// fails. The result is read back into `T`, traps are handled like with
+    /// `EnvMut::catch`.
+    ///
+    /// ```ignore
+    /// let output: String = env.call("process", ("hello", &bytes[..]))?;
+    /// ```
+    pub fn call<A: Args, T: Return>(&self, name: &'static str, args: A) -> Result<T, Error> {
+        let func = self
+            .data()
             .exports
-            .get_with_generics_weak("memory")
-            .map_err(HostEnvInitError::from)?;
-        if let Ok(func) = instance.exports.get_with_generics_weak("__new") {
-            self.fn_new = Some(func)
+            .get_function(name)
+            .map_err(|_| Error::MissingExport(name))?
+            .clone();
+        let mut pinned = Vec::new();
+        let args = args.to_values(self, &mut pinned)?;
+        let results = self.catch(|store| func.call(store, &args))?;
+        T::from_values(&results, self)
+    }
+
+    /// Run a call to the guest and turn a trap following an `abort` into an
+    /// `Error::Abort`, with the message and location given by the guest and
+    /// the wasm backtrace. The other traps are returned as `Error::Runtime`.
+    ///
+    /// The aborts are recorded by the `abort` import in the `Env` it's bound
+    /// to, so it must be bound to the same `FunctionEnv` as this `EnvMut`, as
+    /// `imports` does. With an import bound to another `Env`, the trap of a
+    /// handled abort isn't recognized and is returned as `Error::Runtime`.
+    ///
+    /// ```ignore
+    /// let env = FunctionEnv::new(&mut store, Env::default());
+    /// let import_object = imports(&mut store, &env);
+    /// let instance = Instance::new(&mut store, &module, &import_object)?;
+    /// let env = EnvMut::new(env.into_mut(&mut store));
+    /// env.init(&instance)?;
+    ///
+    /// let run: TypedFunction<i32, i32> = instance.exports.get_typed_function(&*env.store(), "run")?;
+    /// match env.catch(|store| run.call(store, 42)) {
+    ///     // assertion failed at assembly/index.ts:42:3
+    ///     //     at assembly/index/run (<module>[4]:0x2a1)
+    ///     Err(err @ Error::Abort { .. }) => log::error!("{}", err),
+    ///     ...
+    /// }
+    /// ```
+    pub fn catch<T>(
+        &self,
+        call: impl FnOnce(&mut FunctionEnvMut<'a, Env>) -> Result<T, RuntimeError>,
+    ) -> Result<T, Error> {
+        let result = {
+            let mut env = self.borrow_mut()?;
+            env.data_mut().last_abort = None;
+            call(&mut env)
+        };
+        let err = match result {
+            Ok(value) => return Ok(value),
+            Err(err) => err,
+        };
+        let trace = err.trace().to_vec();
+        match err.downcast::<AbortError>() {
+            Ok(abort) => Err(Error::Abort { abort, trace }),
+            Err(err) => match self.last_abort() {
+                Some(abort) => Err(Error::Abort { abort, trace }),
+                _ => Err(Error::Runtime(err)),
+            },
         }
-        if let Ok(func) = instance.exports.get_with_generics_weak("__pin") {
-            self.fn_pin = Some(func)
+    }
+
+    /// Get the runtime type information table of the instance, see
+    /// `Env::rtti`.
+    pub fn rtti(&self) -> Result<Rtti, Error> {
+        self.data().rtti()
+    }
+
+    fn borrow(&self) -> Result<Ref<'_, FunctionEnvMut<'a, Env>>, Error> {
+        self.env
+            .try_borrow()
+            .map_err(|err| Error::Backend(err.into()))
+    }
+
+    fn borrow_mut(&self) -> Result<RefMut<'_, FunctionEnvMut<'a, Env>>, Error> {
+        self.env
+            .try_borrow_mut()
+            .map_err(|err| Error::Backend(err.into()))
+    }
+}
+
+impl<'a> MemoryAccess for EnvMut<'a> {
+    fn data_size(&self) -> u64 {
+        match self.borrow() {
+            Ok(env) => match &env.data().memory {
+                Some(memory) => memory.view(&*env).data_size(),
+                _ => 0,
+            },
+            _ => 0,
         }
-        if let Ok(func) = instance.exports.get_with_generics_weak("__unpin") {
-            self.fn_unpin = Some(func)
+    }
+
+    fn read_bytes(&self, offset: u32, buf: &mut [u8]) -> Result<(), Error> {
+        let env = self.borrow()?;
+        match &env.data().memory {
+            Some(memory) => memory.view(&*env).read_bytes(offset, buf),
+            _ => Err(Error::UninitializedMemory),
         }
-        if let Ok(func) = instance.exports.get_with_generics_weak("__collect") {
-            self.fn_collect = Some(func)
+    }
+
+    fn write_bytes(&self, offset: u32, bytes: &[u8]) -> Result<(), Error> {
+        let env = self.borrow()?;
+        match &env.data().memory {
+            Some(memory) => memory.view(&*env).write_bytes(offset, bytes),
+            _ => Err(Error::UninitializedMemory),
+        }
+    }
+
+    fn utf16_policy(&self) -> Utf16Policy {
+        match self.borrow() {
+            Ok(env) => env.data().utf16,
+            _ => Utf16Policy::default(),
         }
-        self.memory.initialize(mem);
+    }
+}
+
+impl<'a> Runtime for EnvMut<'a> {
+    fn call(&self, name: &'static str, args: &[i32]) -> Result<Option<i32>, Error> {
+        let mut env = self.borrow_mut()?;
+        let func = match name {
+            "__new" => env.data().fn_new.clone(),
+            "__pin" => env.data().fn_pin.clone(),
+            "__unpin" => env.data().fn_unpin.clone(),
+            "__collect" => env.data().fn_collect.clone(),
+            _ => None,
+        };
+        let func = match func {
+            Some(func) => func,
+            _ => return Err(Error::MissingExport(name)),
+        };
+        let args: Vec<Value> = args.iter().map(|arg| Value::I32(*arg)).collect();
+        match func.call(&mut *env, &args)?.first() {
+            Some(Value::I32(value)) => Ok(Some(*value)),
+            _ => Ok(None),
+        }
+    }
+
+    fn rtti(&self) -> Result<Rtti, Error> {
+        EnvMut::rtti(self)
+    }
+
+    fn pinned(&self, offset: u32) -> Result<bool, Error> {
+        Ok(self.borrow()?.data().pinned.contains(&offset))
+    }
+
+    fn set_pinned(&self, offset: u32, pinned: bool) -> Result<(), Error> {
+        let mut env = self.borrow_mut()?;
+        let pins = &mut env.data_mut().pinned;
+        match pinned {
+            true => pins.insert(offset),
+            false => pins.remove(&offset),
+        };
         Ok(())
     }
 }
@@ -0,0 +1,281 @@
+use super::{AnyPtr, BufferPtr, Element, Error, MemoryAccess, Read, Runtime, StringPtr};
+use crate::tools::{
+    alloc, element_offset, pin, read_bytes, read_field, unpin, write_bytes, write_u32,
+};
+
+use std::convert::TryFrom;
+
+// Layout of `Map<K,V>` and `Set<T>`, which share the same ordered hash table
+// https://github.com/AssemblyScript/assemblyscript/blob/main/std/assembly/map.ts
+const BUCKETS: u32 = 0;
+const BUCKETS_MASK: u32 = 4;
+const ENTRIES: u32 = 8;
+const ENTRIES_CAPACITY: u32 = 12;
+const ENTRIES_OFFSET: u32 = 16;
+pub(crate) const ENTRIES_COUNT: u32 = 20;
+const TABLE_SIZE: u32 = 24;
+
+/// Tag of the `taggedNext` field of a removed entry.
+const EMPTY: u32 = 1;
+const INITIAL_CAPACITY: u32 = 4;
+
+// XXHash 32-bit primes, as used in `~lib/util/hash.ts`
+const XXH32_P1: u32 = 2654435761;
+const XXH32_P2: u32 = 2246822519;
+const XXH32_P3: u32 = 3266489917;
+const XXH32_P4: u32 = 668265263;
+const XXH32_P5: u32 = 374761393;
+
+/// A value that can be used as the key of an AssemblyScript `Map` or as the
+/// value of a `Set`.
+///
+/// The hash must be the one computed by the guest, otherwise the keys of a
+/// collection allocated by the host can't be found by the guest.
+pub trait Key: Element {
+    fn hash<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<u32, Error>;
+}
+
+macro_rules! impl_key {
+    ($type:ty, $bits:ty) => {
+        impl Key for $type {
+            fn hash<M: MemoryAccess + ?Sized>(&self, _: &M) -> Result<u32, Error> {
+                Ok(hash_value(*self as $bits as u64, Self::size()))
+            }
+        }
+    };
+}
+
+// Small integers are sign extended to 32 bits by the guest before hashing
+impl_key!(u8, u32);
+impl_key!(i8, i32);
+impl_key!(u16, u32);
+impl_key!(i16, i32);
+impl_key!(u32, u32);
+impl_key!(i32, i32);
+impl_key!(u64, u64);
+impl_key!(i64, i64);
+impl_key!(bool, u32);
+
+impl Key for f32 {
+    fn hash<M: MemoryAccess + ?Sized>(&self, _: &M) -> Result<u32, Error> {
+        Ok(hash_value(u64::from(self.to_bits()), 4))
+    }
+}
+
+impl Key for f64 {
+    fn hash<M: MemoryAccess + ?Sized>(&self, _: &M) -> Result<u32, Error> {
+        Ok(hash_value(self.to_bits(), 8))
+    }
+}
+
+/// Strings are hashed by content.
+impl Key for StringPtr {
+    fn hash<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<u32, Error> {
+        if self.offset() == 0 {
+            return Ok(0);
+        }
+        let bytes = read_bytes(self.offset(), self.size(memory)?, memory)?;
+        Ok(hash_bytes(&bytes))
+    }
+}
+
+/// Other references are hashed by address.
+macro_rules! impl_key_ref {
+    ($type:ty) => {
+        impl Key for $type {
+            fn hash<M: MemoryAccess + ?Sized>(&self, _: &M) -> Result<u32, Error> {
+                Ok(hash_value(u64::from(self.offset()), 4))
+            }
+        }
+    };
+}
+
+impl_key_ref!(BufferPtr);
+impl_key_ref!(AnyPtr);
+
+/// `hash32` and `hash64` of `~lib/util/hash.ts`. Values up to 32 bits are
+/// hashed with their actual `size`.
+fn hash_value(value: u64, size: u32) -> u32 {
+    let mut h = round(XXH32_P5.wrapping_add(size), value as u32);
+    if size > 4 {
+        h = round(h, (value >> 32) as u32);
+    }
+    avalanche(h)
+}
+
+/// `hashStr` of `~lib/util/hash.ts`, which is XXH32 with a zero seed.
+fn hash_bytes(bytes: &[u8]) -> u32 {
+    let mut h = bytes.len() as u32;
+    let mut chunks = bytes.chunks_exact(16);
+    if bytes.len() >= 16 {
+        let mut s = [
+            XXH32_P1.wrapping_add(XXH32_P2),
+            XXH32_P2,
+            0,
+            0u32.wrapping_sub(XXH32_P1),
+        ];
+        for chunk in &mut chunks {
+            for (s, word) in s.iter_mut().zip(chunk.chunks_exact(4)) {
+                *s = s
+                    .wrapping_add(u32_le(word).wrapping_mul(XXH32_P2))
+                    .rotate_left(13)
+                    .wrapping_mul(XXH32_P1);
+            }
+        }
+        h = h
+            .wrapping_add(s[0].rotate_left(1))
+            .wrapping_add(s[1].rotate_left(7))
+            .wrapping_add(s[2].rotate_left(12))
+            .wrapping_add(s[3].rotate_left(18));
+    } else {
+        h = h.wrapping_add(XXH32_P5);
+    }
+    let mut words = chunks.remainder().chunks_exact(4);
+    for word in &mut words {
+        h = round(h, u32_le(word));
+    }
+    for byte in words.remainder() {
+        h = h
+            .wrapping_add(u32::from(*byte).wrapping_mul(XXH32_P5))
+            .rotate_left(11)
+            .wrapping_mul(XXH32_P1);
+    }
+    avalanche(h)
+}
+
+fn round(h: u32, word: u32) -> u32 {
+    h.wrapping_add(word.wrapping_mul(XXH32_P3))
+        .rotate_left(17)
+        .wrapping_mul(XXH32_P4)
+}
+
+fn avalanche(mut h: u32) -> u32 {
+    h ^= h >> 15;
+    h = h.wrapping_mul(XXH32_P2);
+    h ^= h >> 13;
+    h = h.wrapping_mul(XXH32_P3);
+    h ^ (h >> 16)
+}
+
+fn u32_le(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn align_up(offset: u32, align: u32) -> u32 {
+    (offset + align - 1) & !(align - 1)
+}
+
+/// Layout of an entry of the hash table: the key is stored first, followed by
+/// the value (for maps) and the `taggedNext` pointer.
+#[derive(Clone, Copy)]
+pub(crate) struct EntryLayout {
+    pub value: u32,
+    next: u32,
+    size: u32,
+}
+
+impl EntryLayout {
+    pub fn map<K: Element, V: Element>() -> Self {
+        let value = align_up(K::size(), V::size());
+        let next = align_up(value + V::size(), 4);
+        let align = K::size().max(V::size()).max(4);
+        Self {
+            value,
+            next,
+            size: align_up(next + 4, align),
+        }
+    }
+
+    pub fn set<T: Element>() -> Self {
+        let next = align_up(T::size(), 4);
+        Self {
+            value: next,
+            next,
+            size: align_up(next + 4, T::size().max(4)),
+        }
+    }
+}
+
+/// Offsets of the entries still in the table, in insertion order.
+pub(crate) fn entries<M: MemoryAccess + ?Sized>(
+    offset: u32,
+    layout: EntryLayout,
+    memory: &M,
+) -> Result<Vec<u32>, Error> {
+    let entries = read_field(offset, ENTRIES, memory)?;
+    let entries_offset = read_field(offset, ENTRIES_OFFSET, memory)?;
+    let mut live = Vec::new();
+    for i in 0..entries_offset {
+        let entry = element_offset(entries, i, layout.size, memory)?;
+        if read_field(entry, layout.next, memory)? & EMPTY == 0 {
+            live.push(entry);
+        }
+    }
+    Ok(live)
+}
+
+/// Allocate a hash table of class `id` with one entry per key hash in
+/// `hashes`. The key (and value) of the entry `i` are written by `write` in
+/// the slice of the entry.
+pub(crate) fn alloc_table<F, R: Runtime>(
+    id: u32,
+    layout: EntryLayout,
+    hashes: &[u32],
+    mut write: F,
+    env: &R,
+) -> Result<u32, Error>
+where
+    F: FnMut(usize, &mut [u8]),
+{
+    let count = u32::try_from(hashes.len())?;
+    let capacity = count
+        .checked_next_power_of_two()
+        .ok_or(Error::Alloc { size: u32::MAX })?
+        .max(INITIAL_CAPACITY);
+    let buckets_size = capacity
+        .checked_mul(4)
+        .ok_or(Error::Alloc { size: u32::MAX })?;
+    let entries_size = capacity
+        .checked_mul(layout.size)
+        .ok_or(Error::Alloc { size: u32::MAX })?;
+    let entry_size = usize::try_from(layout.size)?;
+
+    // Both buffers aren't referenced yet, pin them while the table is
+    // allocated in case a collection happens.
+    let buckets = alloc(buckets_size, 0, env)?;
+    pin(buckets, env)?;
+    let entries = alloc(entries_size, 0, env)?;
+    pin(entries, env)?;
+    let offset = alloc(TABLE_SIZE, id, env);
+    unpin(entries, env)?;
+    unpin(buckets, env)?;
+    let offset = offset?;
+
+    // Entries are chained in front of the bucket, as `Map#set` does.
+    let mut heads = vec![0u32; usize::try_from(capacity)?];
+    let mut bytes = vec![0u8; usize::try_from(capacity)? * entry_size];
+    for (i, (hash, entry)) in hashes
+        .iter()
+        .zip(bytes.chunks_exact_mut(entry_size))
+        .enumerate()
+    {
+        write(i, entry);
+        let bucket = usize::try_from(hash & (capacity - 1))?;
+        let next = usize::try_from(layout.next)?;
+        entry[next..next + 4].copy_from_slice(&heads[bucket].to_le_bytes());
+        heads[bucket] = entries + u32::try_from(i)? * layout.size;
+    }
+    let heads: Vec<u8> = heads.iter().flat_map(|head| head.to_le_bytes()).collect();
+    write_bytes(buckets, &heads, env)?;
+    write_bytes(entries, &bytes, env)?;
+
+    write_u32(offset + BUCKETS, buckets, env)?;
+    write_u32(offset + BUCKETS_MASK, capacity - 1, env)?;
+    write_u32(offset + ENTRIES, entries, env)?;
+    write_u32(offset + ENTRIES_CAPACITY, capacity, env)?;
+    write_u32(offset + ENTRIES_OFFSET, count, env)?;
+    write_u32(offset + ENTRIES_COUNT, count, env)?;
+
+    pin(offset, env)?;
+    Ok(offset)
+}
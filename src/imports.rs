@@ -0,0 +1,303 @@
+use super::{Arg, Env, EnvMut, Error, MemoryAccess, Read, Return, Runtime, StringPtr};
+use std::fmt;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use wasmer::{
+    AsStoreMut, Exports, Function, FunctionEnv, FunctionEnvMut, FunctionType, Imports,
+    RuntimeError, Type as ValType, Value,
+};
+
+/// Call to `abort` by the guest, usually a failed assertion. When the
+/// handler traps, it can be downcast from the `RuntimeError` returned by the
+/// call to the guest:
+///
+/// ```ignore
+/// match run.call(&mut store) {
+///     Err(err) => match err.downcast::<AbortError>() {
+///         Ok(abort) => assert_eq!(abort.message, "Invalid input"),
+///         Err(err) => return Err(err.into()),
+///     },
+///     Ok(_) => {}
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct AbortError {
+    pub message: String,
+    pub file: String,
+    pub line: i32,
+    pub col: i32,
+}
+
+impl fmt::Display for AbortError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at {}:{}:{}",
+            self.message, self.file, self.line, self.col
+        )
+    }
+}
+
+impl std::error::Error for AbortError {}
+
+/// What the `abort` import does, see `Env::with_abort`.
+#[derive(Clone, Default)]
+pub enum AbortHandler {
+    /// Trap with an `AbortError`, the guest stops.
+    #[default]
+    Trap,
+    /// Log the error with the `log` facade, or `tracing` through its `log`
+    /// compatibility layer. The guest continues.
+    Log,
+    /// Give the error to a callback. The guest continues.
+    Callback(Arc<dyn Fn(&AbortError) + Send + Sync>),
+}
+
+impl AbortHandler {
+    pub fn callback(callback: impl Fn(&AbortError) + Send + Sync + 'static) -> Self {
+        AbortHandler::Callback(Arc::new(callback))
+    }
+}
+
+/// Implementation of `abort`, handled as configured in the environment. The
+/// error is recorded in the environment first, see `Env::last_abort`, so
+/// bind it to the `FunctionEnv` of the `EnvMut` running `EnvMut::catch`.
+pub fn abort(
+    env: FunctionEnvMut<Env>,
+    message: StringPtr,
+    filename: StringPtr,
+    line: i32,
+    col: i32,
+) -> Result<(), RuntimeError> {
+    let env = EnvMut::new(env);
+    let error = AbortError {
+        message: read_nullable(message, &env)?,
+        file: read_nullable(filename, &env)?,
+        line,
+        col,
+    };
+    env.data_mut().set_last_abort(error.clone());
+    let on_abort = env.data().on_abort.clone();
+    match &on_abort {
+        AbortHandler::Trap => return Err(RuntimeError::user(Box::new(error))),
+        AbortHandler::Log => log::error!("{}", error),
+        AbortHandler::Callback(callback) => callback(&error),
+    }
+    Ok(())
+}
+
+/// The message and the file name of `abort` are `null` when missing.
+fn read_nullable<M: MemoryAccess + ?Sized>(
+    ptr: StringPtr,
+    env: &M,
+) -> Result<String, RuntimeError> {
+    match ptr.offset() {
+        0 => Ok(String::new()),
+        _ => ptr
+            .read(env)
+            .map_err(|err: Error| RuntimeError::new(err.to_string())),
+    }
+}
+
+/// Implementation of `trace`, called with a message and `n` of the five
+/// following arguments. The message is printed on stderr unless the
+/// environment has a sink, see `Env::with_trace`.
+#[allow(clippy::too_many_arguments)]
+pub fn trace(
+    env: FunctionEnvMut<Env>,
+    message: StringPtr,
+    n: i32,
+    a0: f64,
+    a1: f64,
+    a2: f64,
+    a3: f64,
+    a4: f64,
+) -> Result<(), RuntimeError> {
+    let env = EnvMut::new(env);
+    let message = match message.read(&env) {
+        Ok(msg) => msg,
+        Err(err) => return Err(RuntimeError::new(err.to_string())),
+    };
+    let args = [a0, a1, a2, a3, a4];
+    let args = &args[..n.clamp(0, 5) as usize];
+    let sink = env.data().trace.clone();
+    match sink {
+        Some(sink) => sink(&message, args),
+        _ if args.is_empty() => eprintln!("trace: {}", message),
+        _ => {
+            let args: Vec<String> = args.iter().map(f64::to_string).collect();
+            eprintln!("trace: {} {}", message, args.join(", "))
+        }
+    }
+    Ok(())
+}
+
+/// Implementation of `seed`, called once to seed `Math.random`. The seed is
+/// the current time unless the environment has one, see `Env::with_seed`.
+pub fn seed(env: FunctionEnvMut<Env>) -> f64 {
+    match env.data().seed {
+        Some(seed) => seed,
+        _ => now(),
+    }
+}
+
+/// Implementation of `Date.now`, milliseconds since the Unix epoch.
+pub fn date_now(_: FunctionEnvMut<Env>) -> f64 {
+    now()
+}
+
+fn now() -> f64 {
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(time) => time.as_millis() as f64,
+        _ => 0.,
+    }
+}
+
+/// Functions of the `env` namespace imported by AssemblyScript modules:
+/// `abort`, `trace`, `seed` and `Date.now`, all bound to `env`. Other
+/// functions can be inserted before registering the namespace.
+pub fn env_exports(store: &mut impl AsStoreMut, env: &FunctionEnv<Env>) -> Exports {
+    let mut exports = Exports::new();
+    exports.insert("abort", Function::new_typed_with_env(store, env, abort));
+    exports.insert("trace", Function::new_typed_with_env(store, env, trace));
+    exports.insert("seed", Function::new_typed_with_env(store, env, seed));
+    exports.insert(
+        "Date.now",
+        Function::new_typed_with_env(store, env, date_now),
+    );
+    exports
+}
+
+/// All the standard imports of AssemblyScript modules, the `env` namespace
+/// and `Date.now` as imported by the compilers older than 0.20. They read
+/// the memory through `env`, which must be initialized with `EnvMut::init`
+/// before calling the guest.
+///
+/// ```ignore
+/// let env = FunctionEnv::new(&mut store, Env::default().with_seed(42.));
+/// let import_object = imports(&mut store, &env);
+/// let instance = Instance::new(&mut store, &module, &import_object)?;
+/// EnvMut::new(env.into_mut(&mut store)).init(&instance)?;
+/// ```
+pub fn imports(store: &mut impl AsStoreMut, env: &FunctionEnv<Env>) -> Imports {
+    let mut import_object = Imports::new();
+    import_object.register_namespace("env", env_exports(store, env));
+    import_object.define(
+        "Date",
+        "now",
+        Function::new_typed_with_env(store, env, date_now),
+    );
+    import_object
+}
+
+/// Result of a function registered with `host_function`, given back to the
+/// guest.
+pub trait HostReturn {
+    /// Wasm types of the results.
+    const TYPES: &'static [ValType];
+
+    fn to_values<R: Runtime>(&self, env: &R) -> Result<Vec<Value>, Error>;
+}
+
+impl HostReturn for () {
+    const TYPES: &'static [ValType] = &[];
+
+    fn to_values<R: Runtime>(&self, _: &R) -> Result<Vec<Value>, Error> {
+        Ok(Vec::new())
+    }
+}
+
+/// Strings and bytes are allocated in the guest and unpinned right away, the
+/// guest holds them from then on.
+impl<T: Arg> HostReturn for T {
+    const TYPES: &'static [ValType] = &[T::TYPE];
+
+    fn to_values<R: Runtime>(&self, env: &R) -> Result<Vec<Value>, Error> {
+        let mut pinned = Vec::new();
+        Ok(vec![self.to_value(env, &mut pinned)?])
+    }
+}
+
+/// A Rust function taking `Args` and returning `R`, which can be registered
+/// as an import with `host_function`.
+pub trait HostFn<Args, R>: Send + Sync + 'static {
+    /// Wasm types of the parameters.
+    fn params() -> Vec<ValType>;
+    /// Decode the parameters given by the guest and call the function.
+    fn call<M: MemoryAccess + ?Sized>(&self, memory: &M, values: &[Value]) -> Result<R, Error>;
+}
+
+macro_rules! host_fn {
+    ($($arg:ident),*) => {
+        impl<Func, R, $($arg: Return),*> HostFn<($($arg,)*), R> for Func
+        where
+            Func: Fn($($arg),*) -> R + Send + Sync + 'static,
+        {
+            #[allow(unused_mut)]
+            fn params() -> Vec<ValType> {
+                let mut params = Vec::new();
+                $(params.extend_from_slice($arg::TYPES);)*
+                params
+            }
+
+            #[allow(non_snake_case, unused_variables, unused_mut, unused_assignments)]
+            fn call<M: MemoryAccess + ?Sized>(
+                &self,
+                memory: &M,
+                values: &[Value],
+            ) -> Result<R, Error> {
+                let mut values = values;
+                $(
+                    let (value, rest) = values.split_at($arg::TYPES.len());
+                    let $arg = $arg::from_values(value, memory)?;
+                    values = rest;
+                )*
+                Ok(self($($arg),*))
+            }
+        }
+    };
+}
+
+host_fn!();
+host_fn!(A);
+host_fn!(A, B);
+host_fn!(A, B, C);
+host_fn!(A, B, C, D);
+host_fn!(A, B, C, D, E);
+host_fn!(A, B, C, D, E, F);
+
+/// Wrap a Rust function into an import. The pointers given by the guest are
+/// read into the parameters, `String` from a string and `Vec<u8>` from an
+/// `ArrayBuffer` for example, and the result is allocated in the guest. A
+/// failure to decode a parameter traps with the `Error`.
+///
+/// ```ignore
+/// fn log(message: String, data: Vec<u8>) -> String {
+///     format!("{}: {} bytes", message, data.len())
+/// }
+///
+/// let mut exports = env_exports(&mut store, &env);
+/// exports.insert("log", host_function(&mut store, &env, log));
+/// ```
+pub fn host_function<Args, R, F>(
+    store: &mut impl AsStoreMut,
+    env: &FunctionEnv<Env>,
+    func: F,
+) -> Function
+where
+    R: HostReturn,
+    F: HostFn<Args, R>,
+{
+    let ty = FunctionType::new(F::params(), R::TYPES.to_vec());
+    Function::new_with_env(
+        store,
+        env,
+        ty,
+        move |env: FunctionEnvMut<Env>, values: &[Value]| {
+            let env = EnvMut::new(env);
+            func.call(&env, values)
+                .and_then(|result| result.to_values(&env))
+                .map_err(|err| RuntimeError::user(Box::new(err)))
+        },
+    )
+}
@@ -8,41 +8,42 @@
 //!
 //! # Helpers
 //!
-//! For the moment this crate implement helpers for the ArrayBuffer and for strings.
-//! Historically the ArrayBuffer is less tested than the string. But the both allow
-//! you to interact with a wasmer instance.
+//! For the moment this crate implement helpers for the ArrayBuffer, for strings
+//! and for `Array<T>` through `ArrayPtr<T>`. Historically the ArrayBuffer is less
+//! tested than the string. But the both allow you to interact with a wasmer instance.
 //!
 //! ```ignore
 //! let wasm_bytes = include_bytes!(concat!(
 //! env!("CARGO_MANIFEST_DIR"),
 //! "/tests/runtime_exported.wat"
 //! ));
-//! let store = Store::default();
+//! let mut store = Store::default();
 //! let module = Module::new(&store, wasm_bytes)?;
 //!
+//! let env = FunctionEnv::new(&mut store, Env::default());
 //! let import_object = imports! {
 //! "env" => {
-//!     "abort" => Function::new_native_with_env(&store, Env::default(), abort),
+//!     "abort" => Function::new_typed_with_env(&mut store, &env, abort),
 //! },
 //! };
 //!
-//! let instance = Instance::new(&module, &import_object)?;
-//! let memory = instance.exports.get_memory("memory").expect("get memory");
-//!
-//! let mut env = Env::default();
-//! env.init(&instance)?;
+//! let instance = Instance::new(&mut store, &module, &import_object)?;
+//! let memory = instance.exports.get_memory("memory")?.clone();
 //!
 //! let get_string = instance
 //! .exports
-//! .get_native_function::<(), StringPtr>("getString")?;
+//! .get_typed_function::<(), StringPtr>(&store, "getString")?;
 //!
-//! let str_ptr = get_string.call()?;
-//! let string = str_ptr.read(memory)?;
+//! let str_ptr = get_string.call(&mut store)?;
+//! let string = str_ptr.read(&memory.view(&store))?;
 //!
 //! assert_eq!(string, "hello test");
 //!
+//! let env = EnvMut::new(env.into_mut(&mut store));
+//! env.init(&instance)?;
+//!
 //! let str_ptr_2 = StringPtr::alloc(&"hello return".to_string(), &env)?;
-//! let string = str_ptr_2.read(memory)?;
+//! let string = str_ptr_2.read(&env)?;
 //! assert_eq!(string, "hello return");
 //! ```
 //!
@@ -53,21 +54,66 @@
 //! share memory between programs and you should consider this in your
 //! project.
 mod any_ptr;
+mod array_ptr;
 mod buffer_ptr;
+mod call;
+mod class_ptr;
+mod element;
 mod env;
+mod hash;
+mod imports;
+mod map_ptr;
+mod module_info;
+mod pinned;
+mod rtti;
+mod runtime;
+mod set_ptr;
+mod static_array_ptr;
+#[cfg(any(feature = "wasmi", feature = "wasmtime"))]
+#[macro_use]
+mod store_env;
+mod stream;
 mod string_ptr;
 mod tools;
+mod typed_array_ptr;
+#[cfg(feature = "wasmi")]
+mod wasmi_env;
+#[cfg(feature = "wasmtime")]
+mod wasmtime_env;
 
 pub use any_ptr::AnyPtr;
 pub use any_ptr::AnyPtrExported;
 pub use any_ptr::Type;
+pub use array_ptr::ArrayPtr;
+pub use as_ffi_bindings_derive::AsClass;
 pub use buffer_ptr::BufferPtr;
-pub use env::Env;
-pub use string_ptr::StringPtr;
-pub use tools::abort;
+pub use call::{Arg, Args, Return};
+pub use class_ptr::{AsClass, ClassFields, ClassPtr};
+pub use element::Element;
+pub use env::{Env, EnvMut, TraceSink};
+pub use hash::Key;
+pub use imports::{
+    abort, date_now, env_exports, host_function, imports, seed, trace, AbortError, AbortHandler,
+    HostFn, HostReturn,
+};
+pub use map_ptr::MapPtr;
+pub use module_info::{ModuleInfo, RuntimeVariant};
+pub use pinned::{Pinned, Pointer};
+pub use rtti::{Rtti, TypeInfo};
+pub use runtime::{MemoryAccess, Runtime};
+pub use set_ptr::SetPtr;
+pub use static_array_ptr::StaticArrayPtr;
+pub use stream::ByteStream;
+pub use string_ptr::{Chars, StringPtr, Utf16Policy};
+pub use typed_array_ptr::TypedArrayPtr;
+#[cfg(feature = "wasmi")]
+pub use wasmi_env::WasmiEnv;
+#[cfg(feature = "wasmtime")]
+pub use wasmtime_env::WasmtimeEnv;
 
 use std::fmt;
-use wasmer::Memory;
+use std::num::TryFromIntError;
+use wasmer::{FrameInfo, RuntimeError};
 
 pub trait Read<T> {
     /// Read the value contained in the given memory at the current pointer
@@ -83,11 +129,11 @@ pub trait Read<T> {
     /// ```ignore
     /// let get_string = instance
     ///     .exports
-    ///     .get_native_function::<(), StringPtr>("getString")?;
-    /// let str_ptr = get_string.call()?;
-    /// let string = str_ptr.read(memory)?;
+    ///     .get_typed_function::<(), StringPtr>(&store, "getString")?;
+    /// let str_ptr = get_string.call(&mut store)?;
+    /// let string = str_ptr.read(&memory.view(&store))?;
     /// ```
-    fn read(&self, memory: &Memory) -> anyhow::Result<T>;
+    fn read<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<T, Error>;
     /// Read the size as indicated in the [AssemblyScript object header](https://www.assemblyscript.org/memory.html#internals)
     ///
     /// # Return
@@ -100,14 +146,14 @@ pub trait Read<T> {
     /// ```ignore
     /// let get_string = instance
     ///     .exports
-    ///     .get_native_function::<(), StringPtr>("getString")?;
-    /// let str_ptr = get_string.call()?;
-    /// let size: u32 = str_ptr.size(memory)?;
+    ///     .get_typed_function::<(), StringPtr>(&store, "getString")?;
+    /// let str_ptr = get_string.call(&mut store)?;
+    /// let size: u32 = str_ptr.size(&memory.view(&store))?;
     /// ```
-    fn size(&self, memory: &Memory) -> anyhow::Result<u32>;
+    fn size<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<u32, Error>;
 }
 
-pub trait Write<T> {
+pub trait Write<T>: Pointer + Sized {
     /// Try to write in the given environment a new value thanks to the
     /// AssemblyScript runtime.
     ///
@@ -116,15 +162,16 @@ pub trait Write<T> {
     /// [AssemblyScript Runtime](https://www.assemblyscript.org/garbage-collection.html#runtime-interface)
     /// or if for some reason the binding failed to read the offset returned.
     ///
-    /// Otherwise, the result return a success containing the new pointer.
+    /// Otherwise, the result return a success containing the new pointer,
+    /// pinned until the returned guard is dropped.
     ///
     /// # Example
     /// ```ignore
-    /// let mut env = Env::default();
+    /// let env = EnvMut::new(env.into_mut(&mut store));
     /// env.init(&instance)?;
     /// let str_ptr = StringPtr::alloc(&"hello return".to_string(), &env)?;
     /// ```
-    fn alloc(value: &T, env: &Env) -> anyhow::Result<Box<Self>>;
+    fn alloc<R: Runtime>(value: &T, env: &R) -> Result<Pinned<Self, R>, Error>;
     /// Try to write in the given environment a value. If the size is
     /// different, we procede to free the previous string and realloc a new
     /// pointer.
@@ -140,26 +187,144 @@ pub trait Write<T> {
     /// # Example
     ///
     /// ```ignore
-    /// let mut env = Env::default();
+    /// let env = EnvMut::new(env.into_mut(&mut store));
     /// env.init(&instance)?;
     /// let string = str_ptr.write(&"hello return".to_string(), &env)?;
     /// ```
-    fn write(&mut self, value: &T, env: &Env) -> anyhow::Result<Box<Self>>;
+    fn write<R: Runtime>(&mut self, value: &T, env: &R) -> Result<Box<Self>, Error>;
     /// Unpin the pointer
-    fn free(self, env: &Env) -> anyhow::Result<()>;
+    fn free<R: Runtime>(self, env: &R) -> Result<(), Error>;
 }
 
+/// Errors returned by the bindings.
+///
+/// The variants allow the host to tell apart a guest returning a bad pointer
+/// (`OutOfBounds`, `InvalidUtf16`) from a misconfigured host (`MissingExport`,
+/// `UninitializedMemory`).
 #[derive(Debug)]
 pub enum Error {
-    Mem(&'static str),
+    /// The offset, or the object behind it, isn't inside the instance memory.
+    /// `memory_size` is the size of the memory in bytes.
+    OutOfBounds { offset: u32, memory_size: u64 },
+    /// A function of the [AssemblyScript Runtime](https://www.assemblyscript.org/garbage-collection.html#runtime-interface)
+    /// or the memory isn't exported by the instance.
+    MissingExport(&'static str),
+    /// The environment hasn't been initialized with an instance memory.
+    UninitializedMemory,
+    /// The memory is a snapshot, like a byte slice, and can't be written.
+    ReadOnly,
+    /// The runtime failed to allocate an object of `size` bytes.
+    Alloc { size: u32 },
+    /// The content isn't valid UTF-16. `offset` counts bytes from the start
+    /// of the string content.
+    InvalidUtf16 { offset: u32 },
+    /// The class id isn't in the runtime type information table.
+    UnknownType(u32),
+    /// No class in the runtime type information matches the flags.
+    TypeNotFound(u32),
+    /// The class has no id to allocate instances, see `AsClass::ID`.
+    MissingClassId(&'static str),
+    /// A serialized value is too short to be decoded.
+    Truncated(usize),
+    /// A value doesn't fit in the 32 bits integers used by the guest.
+    Cast(TryFromIntError),
+    /// The results of an export don't match the type given to `EnvMut::call`.
+    Signature,
+    /// A function of the runtime is exported with another signature, see
+    /// `ModuleInfo::check`.
+    ExportSignature(&'static str),
+    /// A call to the guest trapped.
+    Runtime(RuntimeError),
+    /// A call to the guest trapped after a call to `abort`, see `EnvMut::catch`.
+    /// `trace` is the wasm backtrace of the trap.
+    Abort {
+        abort: AbortError,
+        trace: Vec<FrameInfo>,
+    },
+    /// A call through a runtime other than wasmer failed, or its store is
+    /// already borrowed.
+    Backend(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl Error {
+    /// Build an `OutOfBounds` error for the given offset in `memory`.
+    pub(crate) fn out_of_bounds<M: MemoryAccess + ?Sized>(offset: u32, memory: &M) -> Self {
+        Error::OutOfBounds {
+            offset,
+            memory_size: memory.data_size(),
+        }
+    }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Error::Mem(err) => write!(f, "{}", err),
+            Error::OutOfBounds {
+                offset,
+                memory_size,
+            } => write!(
+                f,
+                "Wrong offset: {} is out of bounds (memory size: {})",
+                offset, memory_size
+            ),
+            Error::MissingExport(name) => write!(f, "Failed to get export {}", name),
+            Error::UninitializedMemory => write!(f, "Uninitialized memory"),
+            Error::ReadOnly => write!(f, "Read-only memory"),
+            Error::Alloc { size } => write!(f, "Failed to allocate {} bytes", size),
+            Error::InvalidUtf16 { offset } => write!(f, "Invalid UTF-16 at offset {}", offset),
+            Error::UnknownType(id) => write!(f, "Unknown class id {}", id),
+            Error::TypeNotFound(flags) => write!(f, "No class found with flags {:#x}", flags),
+            Error::MissingClassId(name) => write!(f, "No class id for {}", name),
+            Error::Truncated(len) => write!(f, "Value too small to be decoded: {} bytes", len),
+            Error::Cast(err) => write!(f, "{}", err),
+            Error::Signature => write!(f, "Unexpected results from the export"),
+            Error::ExportSignature(name) => write!(f, "Unexpected signature of export {}", name),
+            Error::Runtime(err) => write!(f, "{}", err),
+            Error::Abort { abort, trace } => {
+                write!(f, "{}", abort)?;
+                for frame in trace {
+                    write!(
+                        f,
+                        "\n    at {} ({}[{}]:{:#x})",
+                        frame.function_name().unwrap_or("<unnamed>"),
+                        frame.module_name(),
+                        frame.func_index(),
+                        frame.module_offset()
+                    )?;
+                }
+                Ok(())
+            }
+            Error::Backend(err) => write!(f, "{}", err),
         }
     }
 }
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Cast(err) => Some(err),
+            Error::Runtime(err) => Some(err),
+            Error::Abort { abort, .. } => Some(abort),
+            Error::Backend(err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<TryFromIntError> for Error {
+    fn from(err: TryFromIntError) -> Self {
+        Error::Cast(err)
+    }
+}
+
+impl From<RuntimeError> for Error {
+    fn from(err: RuntimeError) -> Self {
+        Error::Runtime(err)
+    }
+}
+
+impl From<Error> for std::io::Error {
+    fn from(err: Error) -> Self {
+        std::io::Error::new(std::io::ErrorKind::Other, err)
+    }
+}
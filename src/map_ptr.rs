@@ -0,0 +1,152 @@
+use super::{Element, Error, Key, MemoryAccess, Pinned, Pointer, Read, Runtime, Write};
+use crate::element::read_element;
+use crate::hash::{alloc_table, entries, EntryLayout, ENTRIES_COUNT};
+use crate::tools::{collect, field_offset, read_field, read_u32, release, unpin};
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use wasmer::FromToNativeWasmType;
+
+/// Pointer to an AssemblyScript `Map<K,V>`.
+///
+/// The map is read as a list of `(key, value)` pairs, in insertion order like
+/// `Map#keys` and `Map#values` would return them. A `Map<string,i32>` is read
+/// as a `Vec<(StringPtr, i32)>`.
+pub struct MapPtr<K, V>(u32, PhantomData<(K, V)>);
+
+impl<K, V> MapPtr<K, V> {
+    pub fn new(offset: u32) -> Self {
+        Self(offset, PhantomData)
+    }
+    pub fn offset(&self) -> u32 {
+        self.0
+    }
+    /// Number of entries in the map
+    pub fn len<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<u32, Error> {
+        read_field(self.offset(), ENTRIES_COUNT, memory)
+    }
+    pub fn is_empty<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<bool, Error> {
+        Ok(self.len(memory)? == 0)
+    }
+}
+
+impl<K: Element + Eq + Hash, V: Element> MapPtr<K, V> {
+    /// Read the map into a `HashMap`, the insertion order is lost.
+    pub fn to_hash_map<M: MemoryAccess + ?Sized>(
+        &self,
+        memory: &M,
+    ) -> Result<HashMap<K, V>, Error> {
+        Ok(self.read(memory)?.into_iter().collect())
+    }
+}
+
+impl<K, V> Clone for MapPtr<K, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K, V> Copy for MapPtr<K, V> {}
+
+impl<K, V> Pointer for MapPtr<K, V> {
+    fn offset(&self) -> u32 {
+        self.0
+    }
+}
+
+unsafe impl<K, V> FromToNativeWasmType for MapPtr<K, V> {
+    type Native = i32;
+    fn to_native(self) -> Self::Native {
+        self.offset() as i32
+    }
+    fn from_native(n: Self::Native) -> Self {
+        Self::new(n as u32)
+    }
+}
+
+impl<K, V> Element for MapPtr<K, V> {
+    const ALIGN: u32 = 2;
+    const MANAGED: bool = true;
+
+    fn from_le_bytes(bytes: &[u8]) -> Self {
+        Self::new(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+    fn write_le_bytes(&self, bytes: &mut [u8]) {
+        bytes.copy_from_slice(&self.offset().to_le_bytes())
+    }
+}
+
+impl<K: Element, V: Element> Read<Vec<(K, V)>> for MapPtr<K, V> {
+    fn read<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<Vec<(K, V)>, Error> {
+        let layout = EntryLayout::map::<K, V>();
+        entries(self.offset(), layout, memory)?
+            .into_iter()
+            .map(|entry| {
+                Ok((
+                    read_element(entry, memory)?,
+                    read_element(field_offset(entry, layout.value, memory)?, memory)?,
+                ))
+            })
+            .collect()
+    }
+
+    /// Size of the map object in bytes, the entries are stored in a separate
+    /// buffer.
+    fn size<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<u32, Error> {
+        if self.offset() < 4 {
+            return Err(Error::out_of_bounds(self.offset(), memory));
+        }
+        read_u32(self.offset() - 4, memory)
+    }
+}
+
+/// The keys are expected to be unique, a key allocated twice with the same
+/// content would be stored twice.
+impl<K: Key, V: Element> Write<Vec<(K, V)>> for MapPtr<K, V> {
+    fn alloc<R: Runtime>(value: &Vec<(K, V)>, env: &R) -> Result<Pinned<MapPtr<K, V>, R>, Error> {
+        let id = env.rtti()?.find_map::<K, V, _>(env)?;
+        let layout = EntryLayout::map::<K, V>();
+        let hashes = value
+            .iter()
+            .map(|(key, _)| key.hash(env))
+            .collect::<Result<Vec<_>, _>>()?;
+        let key_size = usize::try_from(K::size())?;
+        let value_offset = usize::try_from(layout.value)?;
+        let value_end = value_offset + usize::try_from(V::size())?;
+
+        let offset = alloc_table(
+            id,
+            layout,
+            &hashes,
+            |i, entry| {
+                let (key, value) = &value[i];
+                key.write_le_bytes(&mut entry[..key_size]);
+                value.write_le_bytes(&mut entry[value_offset..value_end]);
+            },
+            env,
+        )?;
+        Ok(Pinned::new(MapPtr::new(offset), env))
+    }
+
+    /// The map is always reallocated, the new entries may not fit in its table.
+    fn write<R: Runtime>(
+        &mut self,
+        value: &Vec<(K, V)>,
+        env: &R,
+    ) -> Result<Box<MapPtr<K, V>>, Error> {
+        // unpin old ptr
+        unpin(self.offset(), env)?;
+
+        // collect
+        collect(env)?;
+
+        // alloc with new size
+        Ok(Box::new(MapPtr::alloc(value, env)?.leak()))
+    }
+
+    fn free<R: Runtime>(self, env: &R) -> Result<(), Error> {
+        release(self.offset(), env)
+    }
+}
@@ -0,0 +1,125 @@
+use super::Error;
+use wasmer::{ExternType, FunctionType, Module, Type};
+
+/// Runtime a module is compiled with, given by `--runtime` to the compiler.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RuntimeVariant {
+    /// Allocations only, nothing is ever freed.
+    Stub,
+    /// Collection run by the host with `__collect`.
+    Minimal,
+    /// Collection run along the allocations, the default.
+    Incremental,
+}
+
+/// Functions of the [AssemblyScript Runtime](https://www.assemblyscript.org/garbage-collection.html#runtime-interface)
+/// needed to allocate objects, with their parameters and results.
+const RUNTIME_EXPORTS: [(&str, &[Type], &[Type]); 4] = [
+    ("__new", &[Type::I32, Type::I32], &[Type::I32]),
+    ("__pin", &[Type::I32], &[Type::I32]),
+    ("__unpin", &[Type::I32], &[]),
+    ("__collect", &[], &[]),
+];
+
+/// What a module offers, read before instantiating it: its exports and the
+/// runtime it's compiled with.
+///
+/// ```ignore
+/// let module = Module::new(&store, wasm_bytes)?;
+/// let info = ModuleInfo::new(&module);
+/// // Fails with `Error::MissingExport("__pin")` if the runtime isn't exported
+/// info.check()?;
+/// ```
+#[derive(Debug)]
+pub struct ModuleInfo {
+    exports: Vec<(String, ExternType)>,
+    runtime: Option<RuntimeVariant>,
+}
+
+impl ModuleInfo {
+    pub fn new(module: &Module) -> Self {
+        let exports = module
+            .exports()
+            .map(|export| (export.name().to_string(), export.ty().clone()))
+            .collect();
+        // The runtime interface is the same whatever the variant, only the
+        // symbols of its functions in the name section tell it apart
+        let runtime = module
+            .info()
+            .function_names
+            .values()
+            .find_map(|name| runtime_variant(name));
+        Self { exports, runtime }
+    }
+
+    /// Runtime of the module, read from the name section. `None` if the
+    /// module has no name section, as release builds unless compiled with
+    /// `--debug`, or if the name section has no symbol of the runtime.
+    pub fn runtime(&self) -> Option<RuntimeVariant> {
+        self.runtime
+    }
+
+    /// Functions of the runtime exported by the module.
+    pub fn runtime_exports(&self) -> Vec<&'static str> {
+        RUNTIME_EXPORTS
+            .iter()
+            .map(|(name, _, _)| *name)
+            .filter(|name| self.function(name).is_some())
+            .collect()
+    }
+
+    /// Whether the module is compiled with `--exportRuntime`, which exports
+    /// the runtime functions and `__rtti_base`.
+    pub fn exports_runtime(&self) -> bool {
+        self.runtime_exports().len() == RUNTIME_EXPORTS.len()
+            && self
+                .exports
+                .iter()
+                .any(|(name, ty)| name == "__rtti_base" && matches!(ty, ExternType::Global(_)))
+    }
+
+    /// Exported functions with their signature.
+    pub fn functions(&self) -> impl Iterator<Item = (&str, &FunctionType)> {
+        self.exports.iter().filter_map(|(name, ty)| match ty {
+            ExternType::Function(ty) => Some((name.as_str(), ty)),
+            _ => None,
+        })
+    }
+
+    /// Signature of the exported function `name`.
+    pub fn function(&self, name: &str) -> Option<&FunctionType> {
+        self.functions()
+            .find(|(export, _)| *export == name)
+            .map(|(_, ty)| ty)
+    }
+
+    /// Check the module exports its memory and the runtime functions with
+    /// their signature, which `EnvMut` needs to allocate.
+    pub fn check(&self) -> Result<(), Error> {
+        let memory = self
+            .exports
+            .iter()
+            .any(|(name, ty)| name == "memory" && matches!(ty, ExternType::Memory(_)));
+        if !memory {
+            return Err(Error::MissingExport("memory"));
+        }
+        for (name, params, results) in RUNTIME_EXPORTS {
+            match self.function(name) {
+                Some(ty) if ty.params() == params && ty.results() == results => {}
+                Some(_) => return Err(Error::ExportSignature(name)),
+                _ => return Err(Error::MissingExport(name)),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Variant of the runtime defining the symbol `name`.
+fn runtime_variant(name: &str) -> Option<RuntimeVariant> {
+    match name {
+        name if name.starts_with("~lib/rt/itcms/") => Some(RuntimeVariant::Incremental),
+        name if name.starts_with("~lib/rt/tcms/") => Some(RuntimeVariant::Minimal),
+        name if name.starts_with("~lib/rt/stub/") => Some(RuntimeVariant::Stub),
+        _ => None,
+    }
+}
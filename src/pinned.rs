@@ -0,0 +1,86 @@
+use super::{Error, Runtime, Write};
+use crate::tools::{release, unpin};
+
+use std::ops::Deref;
+
+/// A pointer to an object managed by the AssemblyScript runtime.
+pub trait Pointer {
+    /// Offset of the object in the instance memory.
+    fn offset(&self) -> u32;
+}
+
+/// Guard of an object allocated by the host.
+///
+/// The runtime doesn't know the host holds the object, so it's pinned until
+/// the guard is dropped. The guard dereferences to the pointer, which can be
+/// passed to the guest as long as the guard lives.
+///
+/// ```ignore
+/// let str_ptr = StringPtr::alloc(&"hello".to_string(), &env)?;
+/// say_hello.call(*str_ptr)?;
+/// // `str_ptr` is unpinned here, the string is collected once the guest
+/// // doesn't reference it anymore.
+/// ```
+pub struct Pinned<P: Pointer, R: Runtime> {
+    // Only `None` once the guard has been consumed.
+    ptr: Option<P>,
+    env: R,
+}
+
+impl<P: Pointer, R: Runtime> Pinned<P, R> {
+    /// Guard an object already pinned with `__pin`.
+    pub(crate) fn new(ptr: P, env: &R) -> Self {
+        Self {
+            ptr: Some(ptr),
+            env: env.clone(),
+        }
+    }
+
+    /// Keep the object pinned and return the pointer. It must be freed with
+    /// `Write::free` later, or it will never be collected.
+    pub fn leak(mut self) -> P {
+        self.ptr.take().expect("pinned pointer")
+    }
+
+    /// Unpin the object and return the pointer. The object can then be
+    /// collected as soon as the guest doesn't reference it.
+    pub fn into_inner(mut self) -> Result<P, Error> {
+        let ptr = self.ptr.take().expect("pinned pointer");
+        unpin(ptr.offset(), &self.env)?;
+        Ok(ptr)
+    }
+
+    /// Write `value` in the object. If the object is reallocated, the new
+    /// one is held by the guard instead.
+    pub fn write<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        P: Write<T>,
+    {
+        let ptr = self.ptr.as_mut().expect("pinned pointer");
+        *ptr = *ptr.write(value, &self.env)?;
+        Ok(())
+    }
+
+    /// Unpin the object and run a full collection to reclaim it right away.
+    pub fn free(mut self) -> Result<(), Error> {
+        let ptr = self.ptr.take().expect("pinned pointer");
+        release(ptr.offset(), &self.env)
+    }
+}
+
+impl<P: Pointer, R: Runtime> Deref for Pinned<P, R> {
+    type Target = P;
+
+    fn deref(&self) -> &P {
+        self.ptr.as_ref().expect("pinned pointer")
+    }
+}
+
+impl<P: Pointer, R: Runtime> Drop for Pinned<P, R> {
+    fn drop(&mut self) {
+        if let Some(ptr) = self.ptr.take() {
+            // Errors can't be reported from a drop
+            let _ = unpin(ptr.offset(), &self.env);
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use super::{Element, Error, MemoryAccess};
+use crate::tools::{element_offset, field_offset, read_field, read_u32};
+
+// Flags as defined in `~lib/shared/typeinfo.ts`
+// https://www.assemblyscript.org/runtime.html#runtime-type-information-rtti
+pub(crate) const ARRAYBUFFERVIEW: u32 = 1 << 0;
+pub(crate) const ARRAY: u32 = 1 << 1;
+pub(crate) const STATICARRAY: u32 = 1 << 2;
+pub(crate) const SET: u32 = 1 << 3;
+pub(crate) const MAP: u32 = 1 << 4;
+pub(crate) const POINTERFREE: u32 = 1 << 5;
+pub(crate) const VALUE_ALIGN_0: u32 = 1 << 6;
+pub(crate) const VALUE_SIGNED: u32 = 1 << 11;
+pub(crate) const VALUE_FLOAT: u32 = 1 << 12;
+pub(crate) const VALUE_NULLABLE: u32 = 1 << 13;
+pub(crate) const VALUE_MANAGED: u32 = 1 << 14;
+pub(crate) const KEY_ALIGN_0: u32 = 1 << 15;
+pub(crate) const KEY_SIGNED: u32 = 1 << 20;
+pub(crate) const KEY_FLOAT: u32 = 1 << 21;
+pub(crate) const KEY_NULLABLE: u32 = 1 << 22;
+pub(crate) const KEY_MANAGED: u32 = 1 << 23;
+
+/// Runtime type information table exported by the AssemblyScript compiler
+/// through the `__rtti_base` global.
+///
+/// The table starts with the number of classes, followed by a `flags` and a
+/// `base` u32 for each class id.
+#[derive(Clone, Copy, Debug)]
+pub struct Rtti {
+    base: u32,
+}
+
+impl Rtti {
+    pub fn new(base: u32) -> Self {
+        Self { base }
+    }
+
+    /// Offset of the table in the memory.
+    pub fn offset(&self) -> u32 {
+        self.base
+    }
+
+    /// Number of class ids in the table.
+    pub fn count<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<u32, Error> {
+        read_u32(self.base, memory)
+    }
+
+    /// Read the type information of the class `id`, as found in the object
+    /// header of its instances.
+    pub fn type_info<M: MemoryAccess + ?Sized>(
+        &self,
+        id: u32,
+        memory: &M,
+    ) -> Result<TypeInfo, Error> {
+        if id >= self.count(memory)? {
+            return Err(Error::UnknownType(id));
+        }
+        let entry = element_offset(field_offset(self.base, 4, memory)?, id, 8, memory)?;
+        Ok(TypeInfo {
+            id,
+            flags: read_u32(entry, memory)?,
+            base: read_field(entry, 4, memory)?,
+        })
+    }
+
+    /// Find the first class id described by exactly `flags`.
+    pub fn find<M: MemoryAccess + ?Sized>(
+        &self,
+        flags: u32,
+        memory: &M,
+    ) -> Result<Option<u32>, Error> {
+        for id in 0..self.count(memory)? {
+            if self.type_info(id, memory)?.flags == flags {
+                return Ok(Some(id));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Class id of a collection of `T` described by `flags`. Managed values
+    /// can be nullable, so both variants are looked up.
+    ///
+    /// Note that classes sharing the same layout, like `Array<string>` and
+    /// `Array<Array<i32>>`, also share the same flags. The first id found is
+    /// returned.
+    pub(crate) fn find_collection<T: Element, M: MemoryAccess + ?Sized>(
+        &self,
+        flags: u32,
+        memory: &M,
+    ) -> Result<u32, Error> {
+        let flags = flags | value_flags::<T>();
+        if let Some(id) = self.find(flags, memory)? {
+            return Ok(id);
+        }
+        if T::MANAGED {
+            if let Some(id) = self.find(flags | VALUE_NULLABLE, memory)? {
+                return Ok(id);
+            }
+        }
+        Err(Error::TypeNotFound(flags))
+    }
+
+    /// Class id of a `Map<K,V>`, both the key and the value can be nullable.
+    pub(crate) fn find_map<K: Element, V: Element, M: MemoryAccess + ?Sized>(
+        &self,
+        memory: &M,
+    ) -> Result<u32, Error> {
+        let flags = MAP | key_flags::<K>() | value_flags::<V>();
+        let key_nullable = if K::MANAGED { KEY_NULLABLE } else { 0 };
+        let value_nullable = if V::MANAGED { VALUE_NULLABLE } else { 0 };
+        for nullable in [
+            0,
+            value_nullable,
+            key_nullable,
+            key_nullable | value_nullable,
+        ] {
+            if let Some(id) = self.find(flags | nullable, memory)? {
+                return Ok(id);
+            }
+        }
+        Err(Error::TypeNotFound(flags))
+    }
+}
+
+/// Type information of a single class.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TypeInfo {
+    pub id: u32,
+    pub flags: u32,
+    pub base: u32,
+}
+
+impl TypeInfo {
+    pub fn is_array_buffer_view(&self) -> bool {
+        self.flags & ARRAYBUFFERVIEW != 0
+    }
+    pub fn is_array(&self) -> bool {
+        self.flags & ARRAY != 0
+    }
+    pub fn is_static_array(&self) -> bool {
+        self.flags & STATICARRAY != 0
+    }
+    pub fn is_set(&self) -> bool {
+        self.flags & SET != 0
+    }
+    pub fn is_map(&self) -> bool {
+        self.flags & MAP != 0
+    }
+    /// Instances don't contain any managed reference.
+    pub fn is_pointerfree(&self) -> bool {
+        self.flags & POINTERFREE != 0
+    }
+    /// Log2 of the value size in bytes, for collections.
+    pub fn value_align(&self) -> Option<u32> {
+        align(self.flags, VALUE_ALIGN_0)
+    }
+    pub fn is_value_signed(&self) -> bool {
+        self.flags & VALUE_SIGNED != 0
+    }
+    pub fn is_value_float(&self) -> bool {
+        self.flags & VALUE_FLOAT != 0
+    }
+    pub fn is_value_nullable(&self) -> bool {
+        self.flags & VALUE_NULLABLE != 0
+    }
+    pub fn is_value_managed(&self) -> bool {
+        self.flags & VALUE_MANAGED != 0
+    }
+    /// Log2 of the key size in bytes, for maps.
+    pub fn key_align(&self) -> Option<u32> {
+        align(self.flags, KEY_ALIGN_0)
+    }
+    pub fn is_key_signed(&self) -> bool {
+        self.flags & KEY_SIGNED != 0
+    }
+    pub fn is_key_float(&self) -> bool {
+        self.flags & KEY_FLOAT != 0
+    }
+    pub fn is_key_nullable(&self) -> bool {
+        self.flags & KEY_NULLABLE != 0
+    }
+    pub fn is_key_managed(&self) -> bool {
+        self.flags & KEY_MANAGED != 0
+    }
+    /// Id of the base class, if any.
+    pub fn base_id(&self) -> Option<u32> {
+        if self.base == 0 {
+            None
+        } else {
+            Some(self.base)
+        }
+    }
+}
+
+fn align(flags: u32, align_0: u32) -> Option<u32> {
+    (0..5).find(|shift| flags & (align_0 << shift) != 0)
+}
+
+/// Value flags of `T`, as the AssemblyScript compiler computes them in
+/// `typeToRuntimeFlags`.
+pub(crate) fn value_flags<T: Element>() -> u32 {
+    let mut flags = VALUE_ALIGN_0 << T::ALIGN;
+    if T::SIGNED {
+        flags |= VALUE_SIGNED;
+    }
+    if T::FLOAT {
+        flags |= VALUE_FLOAT;
+    }
+    if T::MANAGED {
+        flags |= VALUE_MANAGED;
+    }
+    if T::NULLABLE {
+        flags |= VALUE_NULLABLE;
+    }
+    flags
+}
+
+/// Key flags of `K`, for maps.
+pub(crate) fn key_flags<K: Element>() -> u32 {
+    let mut flags = KEY_ALIGN_0 << K::ALIGN;
+    if K::SIGNED {
+        flags |= KEY_SIGNED;
+    }
+    if K::FLOAT {
+        flags |= KEY_FLOAT;
+    }
+    if K::MANAGED {
+        flags |= KEY_MANAGED;
+    }
+    if K::NULLABLE {
+        flags |= KEY_NULLABLE;
+    }
+    flags
+}
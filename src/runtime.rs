@@ -0,0 +1,105 @@
+use super::{Error, Rtti, Utf16Policy};
+use std::convert::TryFrom;
+use wasmer::MemoryView;
+
+/// Linear memory of an instance.
+///
+/// The pointers only read and write bytes at given offsets, they work with
+/// any memory implementing this trait. Byte slices implement it to decode a
+/// dump of the memory without instantiating the module:
+///
+/// ```ignore
+/// let dump: Vec<u8> = std::fs::read("memory.bin")?;
+/// let message = StringPtr::new(offset).read(&dump)?;
+/// ```
+pub trait MemoryAccess {
+    /// Size of the memory in bytes
+    fn data_size(&self) -> u64;
+    /// Copy the bytes starting at `offset` into `buf`.
+    fn read_bytes(&self, offset: u32, buf: &mut [u8]) -> Result<(), Error>;
+    /// Copy `bytes` into the memory starting at `offset`.
+    fn write_bytes(&self, offset: u32, bytes: &[u8]) -> Result<(), Error>;
+    /// How strings containing unpaired surrogates are read, lossy by default.
+    fn utf16_policy(&self) -> Utf16Policy {
+        Utf16Policy::Lossy
+    }
+}
+
+/// An instance of an AssemblyScript module: its memory and the exports of
+/// the [AssemblyScript Runtime](https://www.assemblyscript.org/garbage-collection.html#runtime-interface).
+///
+/// `EnvMut` implements it for wasmer, `WasmtimeEnv` for wasmtime and `WasmiEnv`
+/// for wasmi. The guards
+/// returned by `Write::alloc` hold a clone of the runtime to unpin their
+/// object, cloning it should be cheap.
+pub trait Runtime: MemoryAccess + Clone {
+    /// Call the export `name` of the instance. Only the runtime functions
+    /// are called, their arguments and results are all `i32`.
+    fn call(&self, name: &'static str, args: &[i32]) -> Result<Option<i32>, Error>;
+    /// Get the runtime type information table of the instance.
+    fn rtti(&self) -> Result<Rtti, Error>;
+    /// Whether the host pinned the object at `offset` and didn't unpin it
+    /// since. Each runtime variant marks the pinned objects its own way, so
+    /// the host keeps track of its pins.
+    fn pinned(&self, offset: u32) -> Result<bool, Error>;
+    /// Record that the host pinned or unpinned the object at `offset`.
+    fn set_pinned(&self, offset: u32, pinned: bool) -> Result<(), Error>;
+}
+
+/// The view of a wasmer memory, given by `Memory::view`. The bytes are
+/// copied at once and the range is checked by wasmer.
+impl MemoryAccess for MemoryView<'_> {
+    fn data_size(&self) -> u64 {
+        MemoryView::data_size(self)
+    }
+
+    fn read_bytes(&self, offset: u32, buf: &mut [u8]) -> Result<(), Error> {
+        match self.read(u64::from(offset), buf) {
+            Ok(()) => Ok(()),
+            _ => Err(Error::out_of_bounds(offset, self)),
+        }
+    }
+
+    fn write_bytes(&self, offset: u32, bytes: &[u8]) -> Result<(), Error> {
+        match self.write(u64::from(offset), bytes) {
+            Ok(()) => Ok(()),
+            _ => Err(Error::out_of_bounds(offset, self)),
+        }
+    }
+}
+
+/// A snapshot of the memory, it can't be written.
+impl MemoryAccess for [u8] {
+    fn data_size(&self) -> u64 {
+        self.len() as u64
+    }
+
+    fn read_bytes(&self, offset: u32, buf: &mut [u8]) -> Result<(), Error> {
+        let from = usize::try_from(offset)?;
+        match self.get(from..).and_then(|bytes| bytes.get(..buf.len())) {
+            Some(bytes) => {
+                buf.copy_from_slice(bytes);
+                Ok(())
+            }
+            _ => Err(Error::out_of_bounds(offset, self)),
+        }
+    }
+
+    fn write_bytes(&self, _: u32, _: &[u8]) -> Result<(), Error> {
+        Err(Error::ReadOnly)
+    }
+}
+
+impl MemoryAccess for Vec<u8> {
+    fn data_size(&self) -> u64 {
+        self.as_slice().data_size()
+    }
+
+    fn read_bytes(&self, offset: u32, buf: &mut [u8]) -> Result<(), Error> {
+        self.as_slice().read_bytes(offset, buf)
+    }
+
+    fn write_bytes(&self, offset: u32, bytes: &[u8]) -> Result<(), Error> {
+        self.as_slice().write_bytes(offset, bytes)
+    }
+}
@@ -0,0 +1,132 @@
+use super::{Element, Error, Key, MemoryAccess, Pinned, Pointer, Read, Runtime, Write};
+use crate::element::read_element;
+use crate::hash::{alloc_table, entries, EntryLayout, ENTRIES_COUNT};
+use crate::rtti::SET;
+use crate::tools::{collect, read_field, read_u32, release, unpin};
+
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use wasmer::FromToNativeWasmType;
+
+/// Pointer to an AssemblyScript `Set<T>`.
+///
+/// The set is read as a list of values in insertion order, like `Set#values`
+/// would return them.
+pub struct SetPtr<T>(u32, PhantomData<T>);
+
+impl<T> SetPtr<T> {
+    pub fn new(offset: u32) -> Self {
+        Self(offset, PhantomData)
+    }
+    pub fn offset(&self) -> u32 {
+        self.0
+    }
+    /// Number of values in the set
+    pub fn len<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<u32, Error> {
+        read_field(self.offset(), ENTRIES_COUNT, memory)
+    }
+    pub fn is_empty<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<bool, Error> {
+        Ok(self.len(memory)? == 0)
+    }
+}
+
+impl<T: Element + Eq + Hash> SetPtr<T> {
+    /// Read the set into a `HashSet`, the insertion order is lost.
+    pub fn to_hash_set<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<HashSet<T>, Error> {
+        Ok(self.read(memory)?.into_iter().collect())
+    }
+}
+
+impl<T> Clone for SetPtr<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for SetPtr<T> {}
+
+impl<T> Pointer for SetPtr<T> {
+    fn offset(&self) -> u32 {
+        self.0
+    }
+}
+
+unsafe impl<T> FromToNativeWasmType for SetPtr<T> {
+    type Native = i32;
+    fn to_native(self) -> Self::Native {
+        self.offset() as i32
+    }
+    fn from_native(n: Self::Native) -> Self {
+        Self::new(n as u32)
+    }
+}
+
+impl<T> Element for SetPtr<T> {
+    const ALIGN: u32 = 2;
+    const MANAGED: bool = true;
+
+    fn from_le_bytes(bytes: &[u8]) -> Self {
+        Self::new(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+    fn write_le_bytes(&self, bytes: &mut [u8]) {
+        bytes.copy_from_slice(&self.offset().to_le_bytes())
+    }
+}
+
+impl<T: Element> Read<Vec<T>> for SetPtr<T> {
+    fn read<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<Vec<T>, Error> {
+        entries(self.offset(), EntryLayout::set::<T>(), memory)?
+            .into_iter()
+            .map(|entry| read_element(entry, memory))
+            .collect()
+    }
+
+    /// Size of the set object in bytes, the values are stored in a separate
+    /// buffer.
+    fn size<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<u32, Error> {
+        if self.offset() < 4 {
+            return Err(Error::out_of_bounds(self.offset(), memory));
+        }
+        read_u32(self.offset() - 4, memory)
+    }
+}
+
+/// The values are expected to be unique, a value allocated twice with the
+/// same content would be stored twice.
+impl<T: Key> Write<Vec<T>> for SetPtr<T> {
+    fn alloc<R: Runtime>(value: &Vec<T>, env: &R) -> Result<Pinned<SetPtr<T>, R>, Error> {
+        let id = env.rtti()?.find_collection::<T, _>(SET, env)?;
+        let hashes = value
+            .iter()
+            .map(|value| value.hash(env))
+            .collect::<Result<Vec<_>, _>>()?;
+        let size = usize::try_from(T::size())?;
+
+        let offset = alloc_table(
+            id,
+            EntryLayout::set::<T>(),
+            &hashes,
+            |i, entry| value[i].write_le_bytes(&mut entry[..size]),
+            env,
+        )?;
+        Ok(Pinned::new(SetPtr::new(offset), env))
+    }
+
+    /// The set is always reallocated, the new entries may not fit in its table.
+    fn write<R: Runtime>(&mut self, value: &Vec<T>, env: &R) -> Result<Box<SetPtr<T>>, Error> {
+        // unpin old ptr
+        unpin(self.offset(), env)?;
+
+        // collect
+        collect(env)?;
+
+        // alloc with new size
+        Ok(Box::new(SetPtr::alloc(value, env)?.leak()))
+    }
+
+    fn free<R: Runtime>(self, env: &R) -> Result<(), Error> {
+        release(self.offset(), env)
+    }
+}
@@ -0,0 +1,140 @@
+use super::{Element, Error, MemoryAccess, Pinned, Pointer, Read, Runtime, Write};
+use crate::element::{byte_length, read_elements, write_elements};
+use crate::rtti::{POINTERFREE, STATICARRAY};
+use crate::tools::{alloc, collect, pin, read_u32, release, unpin};
+
+use std::convert::TryFrom;
+use std::marker::PhantomData;
+use wasmer::{FromToNativeWasmType, MemoryView, ValueType, WasmSlice};
+
+/// Pointer to an AssemblyScript `StaticArray<T>`.
+///
+/// Unlike `Array<T>`, the elements are stored directly in the object, so they
+/// can be borrowed from the memory with `view` instead of being copied.
+pub struct StaticArrayPtr<T>(u32, PhantomData<T>);
+
+impl<T> StaticArrayPtr<T> {
+    pub fn new(offset: u32) -> Self {
+        Self(offset, PhantomData)
+    }
+    pub fn offset(&self) -> u32 {
+        self.0
+    }
+}
+
+impl<T: Element> StaticArrayPtr<T> {
+    /// Number of elements in the array
+    pub fn len<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<u32, Error> {
+        Ok(size(self.offset(), memory)? >> T::ALIGN)
+    }
+    pub fn is_empty<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<bool, Error> {
+        Ok(self.len(memory)? == 0)
+    }
+}
+
+impl<T: Element + ValueType> StaticArrayPtr<T> {
+    /// Borrow the elements in the instance memory, without copying them.
+    ///
+    /// The view is invalidated if the memory grows, so it shouldn't be held
+    /// while calling functions of the instance.
+    pub fn view<'a>(&self, memory: &'a MemoryView) -> Result<WasmSlice<'a, T>, Error> {
+        let len = self.len(memory)?;
+        WasmSlice::new(memory, u64::from(self.offset()), u64::from(len))
+            .map_err(|_| Error::out_of_bounds(self.offset(), memory))
+    }
+}
+
+impl<T> Clone for StaticArrayPtr<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for StaticArrayPtr<T> {}
+
+impl<T> Pointer for StaticArrayPtr<T> {
+    fn offset(&self) -> u32 {
+        self.0
+    }
+}
+
+unsafe impl<T> FromToNativeWasmType for StaticArrayPtr<T> {
+    type Native = i32;
+    fn to_native(self) -> Self::Native {
+        self.offset() as i32
+    }
+    fn from_native(n: Self::Native) -> Self {
+        Self::new(n as u32)
+    }
+}
+
+impl<T> Element for StaticArrayPtr<T> {
+    const ALIGN: u32 = 2;
+    const MANAGED: bool = true;
+
+    fn from_le_bytes(bytes: &[u8]) -> Self {
+        Self::new(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+    fn write_le_bytes(&self, bytes: &mut [u8]) {
+        bytes.copy_from_slice(&self.offset().to_le_bytes())
+    }
+}
+
+impl<T: Element> Read<Vec<T>> for StaticArrayPtr<T> {
+    fn read<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<Vec<T>, Error> {
+        read_elements(self.offset(), self.len(memory)?, memory)
+    }
+
+    fn size<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<u32, Error> {
+        size(self.offset(), memory)
+    }
+}
+
+impl<T: Element> Write<Vec<T>> for StaticArrayPtr<T> {
+    fn alloc<R: Runtime>(value: &Vec<T>, env: &R) -> Result<Pinned<StaticArrayPtr<T>, R>, Error> {
+        let flags = if T::MANAGED {
+            STATICARRAY
+        } else {
+            STATICARRAY | POINTERFREE
+        };
+        let id = env.rtti()?.find_collection::<T, _>(flags, env)?;
+        let offset = alloc(byte_length::<T>(value.len())?, id, env)?;
+        write_elements(offset, value, env)?;
+
+        pin(offset, env)?;
+        Ok(Pinned::new(StaticArrayPtr::new(offset), env))
+    }
+
+    fn write<R: Runtime>(
+        &mut self,
+        value: &Vec<T>,
+        env: &R,
+    ) -> Result<Box<StaticArrayPtr<T>>, Error> {
+        if self.len(env)? == u32::try_from(value.len())? {
+            write_elements(self.offset(), value, env)?;
+            Ok(Box::new(*self))
+        } else {
+            // unpin old ptr
+            unpin(self.offset(), env)?;
+
+            // collect
+            collect(env)?;
+
+            // alloc with new size
+            Ok(Box::new(StaticArrayPtr::alloc(value, env)?.leak()))
+        }
+    }
+
+    fn free<R: Runtime>(self, env: &R) -> Result<(), Error> {
+        release(self.offset(), env)
+    }
+}
+
+fn size<M: MemoryAccess + ?Sized>(offset: u32, memory: &M) -> Result<u32, Error> {
+    if offset < 4 {
+        return Err(Error::out_of_bounds(offset, memory));
+    }
+    // read -4 offset
+    // https://www.assemblyscript.org/runtime.html#memory-layout
+    read_u32(offset - 4, memory)
+}
@@ -0,0 +1,183 @@
+use super::Error;
+
+/// Define the environment of a backend with a wasmtime-like API: a store
+/// borrowed through a `StoreContextMut`, and `Memory`, `TypedFunc` and `Val`
+/// types. wasmtime and wasmi share it.
+macro_rules! store_env {
+    ($(#[$meta:meta])* $name:ident, $backend:ident) => {
+        $(#[$meta])*
+        pub struct $name<'a, T> {
+            store: ::std::rc::Rc<::std::cell::RefCell<$backend::StoreContextMut<'a, T>>>,
+            memory: $backend::Memory,
+            fn_new: Option<$backend::TypedFunc<(i32, i32), i32>>,
+            fn_pin: Option<$backend::TypedFunc<i32, i32>>,
+            fn_unpin: Option<$backend::TypedFunc<i32, ()>>,
+            fn_collect: Option<$backend::TypedFunc<(), ()>>,
+            rtti_base: Option<u32>,
+            /// Objects pinned by the host, shared by the clones
+            pinned: ::std::rc::Rc<::std::cell::RefCell<::std::collections::HashSet<u32>>>,
+        }
+
+        impl<'a, T> $name<'a, T> {
+            /// Get the memory and the runtime exports of `instance`. `store`
+            /// is either the `Store` or the `Caller` of a host function.
+            pub fn new(
+                store: impl Into<$backend::StoreContextMut<'a, T>>,
+                instance: &$backend::Instance,
+            ) -> Result<Self, $crate::Error> {
+                let mut store = store.into();
+                let memory = match instance.get_memory(&mut store, "memory") {
+                    Some(memory) => memory,
+                    _ => return Err($crate::Error::MissingExport("memory")),
+                };
+                let fn_new = instance.get_typed_func(&mut store, "__new").ok();
+                let fn_pin = instance.get_typed_func(&mut store, "__pin").ok();
+                let fn_unpin = instance.get_typed_func(&mut store, "__unpin").ok();
+                let fn_collect = instance.get_typed_func(&mut store, "__collect").ok();
+                let rtti_base = match instance
+                    .get_global(&mut store, "__rtti_base")
+                    .map(|global| global.get(&mut store))
+                {
+                    Some($backend::Val::I32(base)) => Some(base as u32),
+                    _ => None,
+                };
+                Ok(Self {
+                    store: ::std::rc::Rc::new(::std::cell::RefCell::new(store)),
+                    memory,
+                    fn_new,
+                    fn_pin,
+                    fn_unpin,
+                    fn_collect,
+                    rtti_base,
+                    pinned: Default::default(),
+                })
+            }
+
+            /// Borrow the store, to call the exports of the instance for
+            /// example. The environment can't be used until the borrow is
+            /// released.
+            pub fn store(&self) -> ::std::cell::RefMut<'_, $backend::StoreContextMut<'a, T>> {
+                self.store.borrow_mut()
+            }
+
+            pub fn memory(&self) -> $backend::Memory {
+                self.memory
+            }
+
+            /// Get the runtime type information table of the instance. The
+            /// module must be compiled with `--exportRuntime` (or export
+            /// `__rtti_base`).
+            pub fn rtti(&self) -> Result<$crate::Rtti, $crate::Error> {
+                match self.rtti_base {
+                    Some(base) => Ok($crate::Rtti::new(base)),
+                    _ => Err($crate::Error::MissingExport("__rtti_base")),
+                }
+            }
+        }
+
+        impl<'a, T> Clone for $name<'a, T> {
+            fn clone(&self) -> Self {
+                Self {
+                    store: self.store.clone(),
+                    memory: self.memory,
+                    fn_new: self.fn_new.clone(),
+                    fn_pin: self.fn_pin.clone(),
+                    fn_unpin: self.fn_unpin.clone(),
+                    fn_collect: self.fn_collect.clone(),
+                    rtti_base: self.rtti_base,
+                    pinned: self.pinned.clone(),
+                }
+            }
+        }
+
+        impl<'a, T> $crate::MemoryAccess for $name<'a, T> {
+            fn data_size(&self) -> u64 {
+                match self.store.try_borrow() {
+                    Ok(store) => self.memory.data(&*store).len() as u64,
+                    _ => 0,
+                }
+            }
+
+            fn read_bytes(&self, offset: u32, buf: &mut [u8]) -> Result<(), $crate::Error> {
+                let store = self
+                    .store
+                    .try_borrow()
+                    .map_err(|err| $crate::Error::Backend(err.into()))?;
+                let start = usize::try_from(offset)?;
+                match self.memory.read(&*store, start, buf) {
+                    Ok(()) => Ok(()),
+                    _ => Err($crate::Error::OutOfBounds {
+                        offset,
+                        memory_size: self.memory.data(&*store).len() as u64,
+                    }),
+                }
+            }
+
+            fn write_bytes(&self, offset: u32, bytes: &[u8]) -> Result<(), $crate::Error> {
+                let mut store = self
+                    .store
+                    .try_borrow_mut()
+                    .map_err(|err| $crate::Error::Backend(err.into()))?;
+                let start = usize::try_from(offset)?;
+                match self.memory.write(&mut *store, start, bytes) {
+                    Ok(()) => Ok(()),
+                    _ => Err($crate::Error::OutOfBounds {
+                        offset,
+                        memory_size: self.memory.data(&*store).len() as u64,
+                    }),
+                }
+            }
+        }
+
+        impl<'a, T> $crate::Runtime for $name<'a, T> {
+            fn call(&self, name: &'static str, args: &[i32]) -> Result<Option<i32>, $crate::Error> {
+                use $crate::store_env::export;
+
+                let mut store = self
+                    .store
+                    .try_borrow_mut()
+                    .map_err(|err| $crate::Error::Backend(err.into()))?;
+                let result = match (name, args) {
+                    ("__new", [size, id]) => export(&self.fn_new, name)?
+                        .call(&mut *store, (*size, *id))
+                        .map(Some),
+                    ("__pin", [ptr]) => export(&self.fn_pin, name)?
+                        .call(&mut *store, *ptr)
+                        .map(Some),
+                    ("__unpin", [ptr]) => export(&self.fn_unpin, name)?
+                        .call(&mut *store, *ptr)
+                        .map(|_| None),
+                    ("__collect", []) => export(&self.fn_collect, name)?
+                        .call(&mut *store, ())
+                        .map(|_| None),
+                    _ => return Err($crate::Error::MissingExport(name)),
+                };
+                result.map_err(|err| $crate::Error::Backend(err.into()))
+            }
+
+            fn rtti(&self) -> Result<$crate::Rtti, $crate::Error> {
+                $name::rtti(self)
+            }
+
+            fn pinned(&self, offset: u32) -> Result<bool, $crate::Error> {
+                Ok(self.pinned.borrow().contains(&offset))
+            }
+
+            fn set_pinned(&self, offset: u32, pinned: bool) -> Result<(), $crate::Error> {
+                let mut pins = self.pinned.borrow_mut();
+                match pinned {
+                    true => pins.insert(offset),
+                    false => pins.remove(&offset),
+                };
+                Ok(())
+            }
+        }
+    };
+}
+
+pub(crate) fn export<'f, F>(func: &'f Option<F>, name: &'static str) -> Result<&'f F, Error> {
+    match func {
+        Some(func) => Ok(func),
+        _ => Err(Error::MissingExport(name)),
+    }
+}
@@ -0,0 +1,125 @@
+use super::{BufferPtr, Error, MemoryAccess, StaticArrayPtr};
+use crate::tools::read_u32;
+
+use std::convert::TryFrom;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+/// Cursor over the bytes of an object in the memory, implementing
+/// `std::io::Read`, `Write` and `Seek` to move large contents in chunks
+/// instead of copying them at once.
+///
+/// The size of the object is read from its header on every access, so the
+/// stream never reads or writes past the end of the object. It can't grow
+/// the object: writing past the end writes nothing, like a full slice.
+///
+/// ```ignore
+/// let mut file = File::create("dump.bin")?;
+/// io::copy(&mut buffer_ptr.stream(&env), &mut file)?;
+/// ```
+pub struct ByteStream<'a, M: ?Sized> {
+    memory: &'a M,
+    offset: u32,
+    pos: u64,
+}
+
+impl<'a, M: MemoryAccess + ?Sized> ByteStream<'a, M> {
+    /// Stream the content of the object at `offset`.
+    pub fn new(offset: u32, memory: &'a M) -> Self {
+        Self {
+            memory,
+            offset,
+            pos: 0,
+        }
+    }
+
+    /// Position in bytes from the start of the content.
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+
+    /// Size in bytes of the content, read from the header of the object.
+    pub fn size(&self) -> Result<u32, Error> {
+        if self.offset < 4 {
+            return Err(Error::out_of_bounds(self.offset, self.memory));
+        }
+        read_u32(self.offset - 4, self.memory)
+    }
+
+    /// Offset in memory of the current position and number of bytes left
+    /// from there, at most `len`.
+    fn range(&self, len: usize) -> Result<(u32, usize), Error> {
+        let size = u64::from(self.size()?);
+        let left = usize::try_from(size.saturating_sub(self.pos))?;
+        // The position is lower than the size when bytes are left
+        let offset = self
+            .offset
+            .checked_add(u32::try_from(self.pos.min(size))?)
+            .ok_or_else(|| Error::out_of_bounds(self.offset, self.memory))?;
+        Ok((offset, left.min(len)))
+    }
+}
+
+impl<'a, M: MemoryAccess + ?Sized> Read for ByteStream<'a, M> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let (offset, len) = self.range(buf.len())?;
+        self.memory.read_bytes(offset, &mut buf[..len])?;
+        self.pos += len as u64;
+        Ok(len)
+    }
+}
+
+impl<'a, M: MemoryAccess + ?Sized> Write for ByteStream<'a, M> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let (offset, len) = self.range(buf.len())?;
+        self.memory.write_bytes(offset, &buf[..len])?;
+        self.pos += len as u64;
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a, M: MemoryAccess + ?Sized> Seek for ByteStream<'a, M> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let (base, delta) = match pos {
+            SeekFrom::Start(pos) => {
+                self.pos = pos;
+                return Ok(pos);
+            }
+            SeekFrom::End(delta) => (u64::from(self.size()?), delta),
+            SeekFrom::Current(delta) => (self.pos, delta),
+        };
+        let pos = if delta < 0 {
+            base.checked_sub(delta.unsigned_abs())
+        } else {
+            base.checked_add(delta as u64)
+        };
+        match pos {
+            Some(pos) => {
+                self.pos = pos;
+                Ok(pos)
+            }
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
+
+impl BufferPtr {
+    /// Stream the content of the buffer, see `ByteStream`.
+    pub fn stream<'a, M: MemoryAccess + ?Sized>(&self, memory: &'a M) -> ByteStream<'a, M> {
+        ByteStream::new(self.offset(), memory)
+    }
+}
+
+impl<T> StaticArrayPtr<T> {
+    /// Stream the elements of the array as little endian bytes, see
+    /// `ByteStream`.
+    pub fn stream<'a, M: MemoryAccess + ?Sized>(&self, memory: &'a M) -> ByteStream<'a, M> {
+        ByteStream::new(self.offset(), memory)
+    }
+}
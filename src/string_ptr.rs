@@ -1,19 +1,306 @@
-use crate::tools::export_asr;
+use crate::tools::{alloc, collect, field_offset, pin, read_u32, release, unpin, write_bytes};
 
-use super::{Env, Memory, Read, Write};
+use super::{Error, MemoryAccess, Pinned, Pointer, Read, Runtime, Write};
 
-use std::convert::{TryFrom, TryInto};
-use wasmer::{Array, FromToNativeWasmType, Value, WasmPtr};
+use std::convert::TryFrom;
+use wasmer::FromToNativeWasmType;
+
+/// Bytes read at once by `Chars`, an even number of bytes.
+const CHUNK: usize = 512;
+
+/// How strings are decoded when they contain unpaired surrogates, which are
+/// valid in AssemblyScript but can't be represented in a Rust `String`.
+///
+/// The memory chooses the policy with `MemoryAccess::utf16_policy`, an
+/// `EnvMut` follows its `Env`, set up with `Env::with_utf16`. The other
+/// memories, like a `MemoryView` or a snapshot, are lossy. The code units can
+/// be read as they are with `StringPtr::read_utf16` or `StringPtr::read_wtf8`
+/// whatever the policy.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Utf16Policy {
+    /// Replace the unpaired surrogates with `U+FFFD`.
+    #[default]
+    Lossy,
+    /// Fail with `Error::InvalidUtf16` at the first unpaired surrogate.
+    Strict,
+    /// Keep the unpaired surrogates, encoded as WTF-8 by
+    /// `StringPtr::read_text`. A `String` can't hold them, so the reads into
+    /// a `String` fail with `Error::InvalidUtf16` like `Strict` rather than
+    /// changing the string.
+    Raw,
+}
 
 #[derive(Clone, Copy)]
-pub struct StringPtr(WasmPtr<u16, Array>);
+pub struct StringPtr(u32);
 
 impl StringPtr {
     pub fn new(offset: u32) -> Self {
-        Self(WasmPtr::new(offset))
+        Self(offset)
     }
     pub fn offset(&self) -> u32 {
-        self.0.offset()
+        self.0
+    }
+
+    /// Iterate over the characters of the string, decoded from the memory by
+    /// chunks without allocating. Unpaired surrogates are replaced by
+    /// `U+FFFD`, whatever the policy of the memory.
+    ///
+    /// The iteration stops early if the memory can't be read anymore, which
+    /// only happens with a memory shared with another borrower.
+    pub fn chars<'a, M: MemoryAccess + ?Sized>(
+        &self,
+        memory: &'a M,
+    ) -> Result<Chars<'a, M>, Error> {
+        let end = match self.offset().checked_add(self.size(memory)? & !1) {
+            Some(end) if u64::from(end) <= memory.data_size() => end,
+            _ => return Err(Error::out_of_bounds(self.offset(), memory)),
+        };
+        Ok(Chars {
+            memory,
+            start: self.offset(),
+            offset: self.offset(),
+            end,
+            units: [0; CHUNK / 2],
+            pos: 0,
+            len: 0,
+            error: None,
+        })
+    }
+
+    /// Append the string to `out`, which can be reused between reads to
+    /// avoid allocating. Chunks of ASCII characters are copied as is.
+    /// Unpaired surrogates are handled according to the `Utf16Policy` of
+    /// the memory, `out` may be partially written unless it's lossy.
+    ///
+    /// ```ignore
+    /// let mut key = String::new();
+    /// for ptr in keys {
+    ///     key.clear();
+    ///     ptr.write_into(memory, &mut key)?;
+    /// }
+    /// ```
+    pub fn write_into<M: MemoryAccess + ?Sized>(
+        &self,
+        memory: &M,
+        out: &mut String,
+    ) -> Result<(), Error> {
+        let strict = memory.utf16_policy() != Utf16Policy::Lossy;
+        let mut chars = self.chars(memory)?;
+        loop {
+            if let Some(ascii) = chars.ascii_chunk() {
+                out.extend(ascii.iter().map(|unit| *unit as u8 as char));
+                continue;
+            }
+            match chars.decode() {
+                Some(Ok(c)) => out.push(c),
+                Some(Err((offset, _))) if strict => return Err(Error::InvalidUtf16 { offset }),
+                Some(Err(_)) => out.push(char::REPLACEMENT_CHARACTER),
+                None => break,
+            }
+        }
+        match chars.error {
+            Some(err) => Err(err),
+            _ => Ok(()),
+        }
+    }
+
+    /// Length of the string in UTF-16 code units, its `length` in
+    /// AssemblyScript. Characters outside of the Basic Multilingual Plane
+    /// count twice.
+    pub fn utf16_len<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<u32, Error> {
+        Ok(self.size(memory)? / 2)
+    }
+
+    /// Read the string, failing with `Error::InvalidUtf16` at the first
+    /// unpaired surrogate whatever the policy of the memory.
+    pub fn read_strict<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<String, Error> {
+        let mut string = String::new();
+        let mut chars = self.chars(memory)?;
+        while let Some(c) = chars.decode() {
+            match c {
+                Ok(c) => string.push(c),
+                Err((offset, _)) => return Err(Error::InvalidUtf16 { offset }),
+            }
+        }
+        match chars.error {
+            Some(err) => Err(err),
+            _ => Ok(string),
+        }
+    }
+
+    /// Read the UTF-16 code units of the string as they are, unpaired
+    /// surrogates included.
+    pub fn read_utf16<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<Vec<u16>, Error> {
+        let mut bytes = vec![0; usize::try_from(self.size(memory)? & !1)?];
+        memory.read_bytes(self.offset(), &mut bytes)?;
+        Ok(bytes
+            .chunks_exact(2)
+            .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+            .collect())
+    }
+
+    /// Read the string as [WTF-8](https://simonsapin.github.io/wtf-8/): UTF-8
+    /// where the unpaired surrogates are encoded like the other code points.
+    /// The bytes are valid UTF-8 if the string has no unpaired surrogate,
+    /// and the string can be written back without loss.
+    pub fn read_wtf8<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<Vec<u8>, Error> {
+        self.encode(memory, Utf16Policy::Raw)
+    }
+
+    /// Read the string as UTF-8 bytes, the unpaired surrogates handled
+    /// according to the `Utf16Policy` of the memory: replaced with `U+FFFD`,
+    /// rejected, or kept as WTF-8 with `Utf16Policy::Raw`.
+    pub fn read_text<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<Vec<u8>, Error> {
+        self.encode(memory, memory.utf16_policy())
+    }
+
+    fn encode<M: MemoryAccess + ?Sized>(
+        &self,
+        memory: &M,
+        policy: Utf16Policy,
+    ) -> Result<Vec<u8>, Error> {
+        let mut bytes = Vec::new();
+        let mut chars = self.chars(memory)?;
+        while let Some(c) = chars.decode() {
+            let c = match (c, policy) {
+                (Ok(c), _) => c,
+                (Err(_), Utf16Policy::Lossy) => char::REPLACEMENT_CHARACTER,
+                (Err((offset, _)), Utf16Policy::Strict) => {
+                    return Err(Error::InvalidUtf16 { offset })
+                }
+                (Err((_, unit)), Utf16Policy::Raw) => {
+                    bytes.extend_from_slice(&[
+                        0xE0 | (unit >> 12) as u8,
+                        0x80 | (unit >> 6 & 0x3F) as u8,
+                        0x80 | (unit & 0x3F) as u8,
+                    ]);
+                    continue;
+                }
+            };
+            bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+        }
+        match chars.error {
+            Some(err) => Err(err),
+            _ => Ok(bytes),
+        }
+    }
+
+    /// Compare the string with `value` without reading it into a `String`.
+    pub fn eq_str<M: MemoryAccess + ?Sized>(&self, memory: &M, value: &str) -> Result<bool, Error> {
+        let size = self.size(memory)? & !1;
+        if u64::from(size) != 2 * value.encode_utf16().count() as u64 {
+            return Ok(false);
+        }
+        let mut expected = value.encode_utf16();
+        let mut bytes = [0; CHUNK];
+        let mut offset = 0;
+        while offset < size {
+            let chunk = &mut bytes[..(size - offset).min(CHUNK as u32) as usize];
+            memory.read_bytes(field_offset(self.offset(), offset, memory)?, chunk)?;
+            for unit in chunk.chunks_exact(2) {
+                if expected.next() != Some(u16::from_le_bytes([unit[0], unit[1]])) {
+                    return Ok(false);
+                }
+            }
+            offset += chunk.len() as u32;
+        }
+        Ok(true)
+    }
+}
+
+/// Characters of a string in the memory, see `StringPtr::chars`.
+pub struct Chars<'a, M: ?Sized> {
+    memory: &'a M,
+    // Offset of the string content
+    start: u32,
+    // Offset of the next chunk
+    offset: u32,
+    end: u32,
+    units: [u16; CHUNK / 2],
+    pos: usize,
+    len: usize,
+    error: Option<Error>,
+}
+
+impl<'a, M: MemoryAccess + ?Sized> Chars<'a, M> {
+    /// Read the next chunk once the current one is consumed.
+    fn fill(&mut self) -> bool {
+        if self.pos < self.len {
+            return true;
+        }
+        if self.offset >= self.end || self.error.is_some() {
+            return false;
+        }
+        let mut bytes = [0; CHUNK];
+        let chunk = &mut bytes[..(self.end - self.offset).min(CHUNK as u32) as usize];
+        if let Err(err) = self.memory.read_bytes(self.offset, chunk) {
+            self.error = Some(err);
+            return false;
+        }
+        for (unit, bytes) in self.units.iter_mut().zip(chunk.chunks_exact(2)) {
+            *unit = u16::from_le_bytes([bytes[0], bytes[1]]);
+        }
+        self.offset += chunk.len() as u32;
+        self.pos = 0;
+        self.len = chunk.len() / 2;
+        true
+    }
+
+    fn unit(&mut self) -> Option<u16> {
+        if !self.fill() {
+            return None;
+        }
+        self.pos += 1;
+        Some(self.units[self.pos - 1])
+    }
+
+    /// Take a whole chunk if it only contains ASCII characters.
+    fn ascii_chunk(&mut self) -> Option<&[u16]> {
+        if self.pos < self.len || !self.fill() {
+            return None;
+        }
+        let units = &self.units[..self.len];
+        if units.iter().all(|unit| *unit < 0x80) {
+            self.pos = self.len;
+            Some(units)
+        } else {
+            None
+        }
+    }
+
+    /// Decode the next character, or return an unpaired surrogate with its
+    /// offset from the start of the string content.
+    fn decode(&mut self) -> Option<Result<char, (u32, u16)>> {
+        let unit = self.unit()?;
+        if let Some(c) = char::from_u32(u32::from(unit)) {
+            return Some(Ok(c));
+        }
+        let offset = self.offset - 2 * (self.len - self.pos + 1) as u32 - self.start;
+        // High surrogate, the low one may be in the next chunk
+        if unit < 0xDC00 && self.fill() {
+            let low = self.units[self.pos];
+            if (0xDC00..0xE000).contains(&low) {
+                self.pos += 1;
+                let c = 0x10000 + ((u32::from(unit) - 0xD800) << 10) + (u32::from(low) - 0xDC00);
+                return char::from_u32(c).map(Ok);
+            }
+        }
+        Some(Err((offset, unit)))
+    }
+}
+
+impl<'a, M: MemoryAccess + ?Sized> Iterator for Chars<'a, M> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        self.decode()
+            .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+    }
+}
+
+impl Pointer for StringPtr {
+    fn offset(&self) -> u32 {
+        self.0
     }
 }
 
@@ -28,105 +315,69 @@ unsafe impl FromToNativeWasmType for StringPtr {
 }
 
 impl Read<String> for StringPtr {
-    fn read(&self, memory: &Memory) -> anyhow::Result<String> {
-        let size = self.size(memory)?;
-        // we need size / 2 because assemblyscript counts bytes
-        // while deref considers u16 elements
-        if let Some(buf) = self.0.deref(memory, 0, size / 2) {
-            let input: Vec<u16> = buf.iter().map(|b| b.get()).collect();
-            Ok(String::from_utf16_lossy(&input))
-        } else {
-            anyhow::bail!("Wrong offset: can't read buf")
-        }
+    fn read<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<String, Error> {
+        // assemblyscript counts bytes, the content is UTF-16 encoded
+        let len = u64::from(self.size(memory)? / 2).min(memory.data_size());
+        let mut string = String::with_capacity(usize::try_from(len)?);
+        self.write_into(memory, &mut string)?;
+        Ok(string)
     }
 
-    fn size(&self, memory: &Memory) -> anyhow::Result<u32> {
-        size(self.0.offset(), memory)
+    fn size<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<u32, Error> {
+        size(self.0, memory)
     }
 }
 
 impl Write<String> for StringPtr {
-    fn alloc(value: &String, env: &Env) -> anyhow::Result<Box<StringPtr>> {
-        let new = export_asr!(fn_new, env);
-        let size = i32::try_from(value.len())?;
-
-        let offset = u32::try_from(
-            match new.call(&[Value::I32(size << 1), Value::I32(1)])?.get(0) {
-                Some(val) => match val.i32() {
-                    Some(i) => i,
-                    _ => anyhow::bail!("Failed to allocate"),
-                },
-                _ => anyhow::bail!("Failed to allocate"),
-            },
-        )?;
+    fn alloc<R: Runtime>(value: &String, env: &R) -> Result<Pinned<StringPtr, R>, Error> {
+        let offset = alloc(utf16_size(value)?, 1, env)?;
         write_str(offset, value, env)?;
 
         // pin
-        let pin = export_asr!(fn_pin, env);
-        pin.call(&[Value::I32(offset.try_into()?)])?;
+        pin(offset, env)?;
 
-        Ok(Box::new(StringPtr::new(offset)))
+        Ok(Pinned::new(StringPtr::new(offset), env))
     }
 
-    fn write(&mut self, value: &String, env: &Env) -> anyhow::Result<Box<StringPtr>> {
-        let memory = match env.memory.get_ref() {
-            Some(mem) => mem,
-            _ => anyhow::bail!("Cannot get memory"),
-        };
-        let prev_size = size(self.offset(), memory)?;
-        let new_size = u32::try_from(value.len())? << 1;
+    fn write<R: Runtime>(&mut self, value: &String, env: &R) -> Result<Box<StringPtr>, Error> {
+        let prev_size = size(self.offset(), env)?;
+        let new_size = utf16_size(value)?;
         if prev_size == new_size {
             write_str(self.offset(), value, env)?;
             Ok(Box::new(*self))
         } else {
             // unpin old ptr
-            let unpin = export_asr!(fn_unpin, env);
-            unpin.call(&[Value::I32(self.offset().try_into()?)])?;
+            unpin(self.offset(), env)?;
 
             // collect
-            let collect = export_asr!(fn_collect, env);
-            collect.call(&[])?;
+            collect(env)?;
 
             // alloc with new size
-            StringPtr::alloc(value, env)
+            Ok(Box::new(StringPtr::alloc(value, env)?.leak()))
         }
     }
 
-    fn free(self, env: &Env) -> anyhow::Result<()> {
-        // unpin
-        let unpin = export_asr!(fn_unpin, env);
-        unpin.call(&[Value::I32(self.offset().try_into()?)])?;
-
-        // collect
-        let collect = export_asr!(fn_collect, env);
-        collect.call(&[])?;
-        Ok(())
+    fn free<R: Runtime>(self, env: &R) -> Result<(), Error> {
+        release(self.offset(), env)
     }
 }
 
-fn write_str(offset: u32, value: &str, env: &Env) -> anyhow::Result<()> {
-    let utf16 = value.encode_utf16();
-    let view = match env.memory.get_ref() {
-        Some(mem) => mem.view::<u16>(),
-        _ => anyhow::bail!("Uninitialized memory"),
-    };
-    // We count in 32 so we have to divide by 2
-    let from = usize::try_from(offset)? / 2;
-    for (bytes, cell) in utf16.into_iter().zip(view[from..from + value.len()].iter()) {
-        cell.set(bytes);
-    }
-    Ok(())
+fn write_str<M: MemoryAccess + ?Sized>(offset: u32, value: &str, memory: &M) -> Result<(), Error> {
+    let bytes: Vec<u8> = value.encode_utf16().flat_map(u16::to_le_bytes).collect();
+    write_bytes(offset, &bytes, memory)
+}
+
+/// Size in bytes of `value` encoded in UTF-16.
+fn utf16_size(value: &str) -> Result<u32, Error> {
+    let len = u32::try_from(value.encode_utf16().count())?;
+    len.checked_mul(2).ok_or(Error::Alloc { size: u32::MAX })
 }
 
-fn size(offset: u32, memory: &Memory) -> anyhow::Result<u32> {
+fn size<M: MemoryAccess + ?Sized>(offset: u32, memory: &M) -> Result<u32, Error> {
     if offset < 4 {
-        anyhow::bail!("Wrong offset: less than 2")
+        return Err(Error::out_of_bounds(offset, memory));
     }
     // read -4 offset
     // https://www.assemblyscript.org/memory.html#internals
-    if let Some(cell) = memory.view::<u32>().get(offset as usize / (32 / 8) - 1) {
-        Ok(cell.get())
-    } else {
-        anyhow::bail!("Wrong offset: can't read size")
-    }
+    read_u32(offset - 4, memory).map_err(|_| Error::out_of_bounds(offset, memory))
 }
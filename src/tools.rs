@@ -1,35 +1,100 @@
-use super::{Env, Read, StringPtr};
-
-// if get_string throws an exception abort for some reason is being called
-pub fn abort(
-    env: &Env,
-    message: StringPtr,
-    filename: StringPtr,
-    line: i32,
-    col: i32,
-) -> Result<(), wasmer::RuntimeError> {
-    let memory = match env.memory.get_ref() {
-        Some(mem) => mem,
-        _ => return Err(wasmer::RuntimeError::new("Cannot get memory")),
-    };
-    let message = match message.read(memory) {
-        Ok(msg) => msg,
-        Err(err) => return Err(wasmer::RuntimeError::new(err.to_string())),
-    };
-    let filename = match filename.read(memory) {
-        Ok(filename) => filename,
-        Err(err) => return Err(wasmer::RuntimeError::new(err.to_string())),
-    };
-    eprintln!("Error: {} at {}:{} col: {}", message, filename, line, col);
+use super::{Error, MemoryAccess, Runtime};
+use std::convert::TryFrom;
+
+/// Allocate a new object of `size` bytes with the class `id` through `__new`.
+pub(crate) fn alloc<R: Runtime>(size: u32, id: u32, env: &R) -> Result<u32, Error> {
+    match env.call("__new", &[i32::try_from(size)?, id as i32])? {
+        Some(offset) => Ok(offset as u32),
+        _ => Err(Error::Alloc { size }),
+    }
+}
+
+pub(crate) fn pin<R: Runtime>(offset: u32, env: &R) -> Result<(), Error> {
+    env.call("__pin", &[offset as i32])?;
+    env.set_pinned(offset, true)
+}
+
+pub(crate) fn unpin<R: Runtime>(offset: u32, env: &R) -> Result<(), Error> {
+    env.call("__unpin", &[offset as i32])?;
+    env.set_pinned(offset, false)
+}
+
+pub(crate) fn collect<R: Runtime>(env: &R) -> Result<(), Error> {
+    env.call("__collect", &[])?;
     Ok(())
 }
 
-macro_rules! export_asr {
-    ($func_name:ident, $env:expr) => {
-        match $env.$func_name.as_ref() {
-            Some(res) => res,
-            _ => anyhow::bail!("Failed to get func"),
-        }
-    };
+/// Release an object allocated by the host: unpin it if it's still pinned,
+/// then run a full collection so its memory is reclaimed right away.
+pub(crate) fn release<R: Runtime>(offset: u32, env: &R) -> Result<(), Error> {
+    if env.pinned(offset)? {
+        unpin(offset, env)?;
+    }
+    collect(env)
+}
+
+pub(crate) fn read_bytes<M: MemoryAccess + ?Sized>(
+    offset: u32,
+    len: u32,
+    memory: &M,
+) -> Result<Vec<u8>, Error> {
+    let mut bytes = vec![0; usize::try_from(len)?];
+    memory.read_bytes(offset, &mut bytes)?;
+    Ok(bytes)
+}
+
+pub(crate) fn write_bytes<M: MemoryAccess + ?Sized>(
+    offset: u32,
+    value: &[u8],
+    memory: &M,
+) -> Result<(), Error> {
+    memory.write_bytes(offset, value)
+}
+
+pub(crate) fn read_u32<M: MemoryAccess + ?Sized>(offset: u32, memory: &M) -> Result<u32, Error> {
+    let mut bytes = [0; 4];
+    memory.read_bytes(offset, &mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+pub(crate) fn write_u32<M: MemoryAccess + ?Sized>(
+    offset: u32,
+    value: u32,
+    memory: &M,
+) -> Result<(), Error> {
+    memory.write_bytes(offset, &value.to_le_bytes())
+}
+
+/// Offset of the field at `field` bytes of the object at `offset`. A corrupt
+/// offset can't wrap around the address space.
+pub(crate) fn field_offset<M: MemoryAccess + ?Sized>(
+    offset: u32,
+    field: u32,
+    memory: &M,
+) -> Result<u32, Error> {
+    offset
+        .checked_add(field)
+        .ok_or_else(|| Error::out_of_bounds(offset, memory))
+}
+
+/// Offset of the element `index` of `size` bytes stored from `offset`.
+pub(crate) fn element_offset<M: MemoryAccess + ?Sized>(
+    offset: u32,
+    index: u32,
+    size: u32,
+    memory: &M,
+) -> Result<u32, Error> {
+    index
+        .checked_mul(size)
+        .and_then(|delta| offset.checked_add(delta))
+        .ok_or_else(|| Error::out_of_bounds(offset, memory))
+}
+
+/// Read the `u32` field at `field` bytes of the object at `offset`.
+pub(crate) fn read_field<M: MemoryAccess + ?Sized>(
+    offset: u32,
+    field: u32,
+    memory: &M,
+) -> Result<u32, Error> {
+    read_u32(field_offset(offset, field, memory)?, memory)
 }
-pub(crate) use export_asr;
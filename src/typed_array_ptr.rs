@@ -0,0 +1,198 @@
+use super::{
+    AnyPtr, BufferPtr, Element, Error, MemoryAccess, Pinned, Pointer, Read, Runtime, Write,
+};
+use crate::element::{byte_length, read_elements, write_elements};
+use crate::rtti::ARRAYBUFFERVIEW;
+use crate::tools::{alloc, collect, element_offset, pin, read_field, release, unpin, write_u32};
+
+use std::convert::TryFrom;
+use std::marker::PhantomData;
+use wasmer::{FromToNativeWasmType, MemoryView, ValueType, WasmSlice};
+
+// Layout of `ArrayBufferView`
+// https://www.assemblyscript.org/runtime.html#memory-layout
+const BUFFER: u32 = 0;
+const DATA_START: u32 = 4;
+const BYTE_LENGTH: u32 = 8;
+const VIEW_SIZE: u32 = 12;
+
+/// Pointer to an AssemblyScript typed array, like `Uint8Array`, `Int32Array`
+/// or `Float64Array`.
+///
+/// A typed array is a view over a part of an `ArrayBuffer`, `T` is the type of
+/// its elements (`f64` for a `Float64Array`). Typed arrays created with
+/// `subarray` share the buffer of their parent, so the elements are read from
+/// `dataStart` and not from the start of the buffer.
+pub struct TypedArrayPtr<T>(u32, PhantomData<T>);
+
+impl<T> TypedArrayPtr<T> {
+    pub fn new(offset: u32) -> Self {
+        Self(offset, PhantomData)
+    }
+    pub fn offset(&self) -> u32 {
+        self.0
+    }
+    /// The `ArrayBuffer` the array is a view of.
+    pub fn buffer<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<BufferPtr, Error> {
+        Ok(BufferPtr::new(read_field(self.offset(), BUFFER, memory)?))
+    }
+    /// Offset in bytes of the first element from the start of the buffer.
+    pub fn byte_offset<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<u32, Error> {
+        let buffer = read_field(self.offset(), BUFFER, memory)?;
+        let data_start = read_field(self.offset(), DATA_START, memory)?;
+        match data_start.checked_sub(buffer) {
+            Some(byte_offset) => Ok(byte_offset),
+            _ => Err(Error::out_of_bounds(data_start, memory)),
+        }
+    }
+}
+
+impl<T: Element> TypedArrayPtr<T> {
+    /// Number of elements in the array
+    pub fn len<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<u32, Error> {
+        Ok(read_field(self.offset(), BYTE_LENGTH, memory)? >> T::ALIGN)
+    }
+    pub fn is_empty<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<bool, Error> {
+        Ok(self.len(memory)? == 0)
+    }
+
+    /// Create a new typed array sharing the buffer of this one, from the
+    /// element `begin` to `end` (excluded), as `TypedArray#subarray` does.
+    pub fn subarray<R: Runtime>(
+        &self,
+        begin: u32,
+        end: u32,
+        env: &R,
+    ) -> Result<Pinned<Self, R>, Error> {
+        let len = self.len(env)?;
+        let end = end.min(len);
+        let begin = begin.min(end);
+        let buffer = read_field(self.offset(), BUFFER, env)?;
+        let data_start = element_offset(
+            read_field(self.offset(), DATA_START, env)?,
+            begin,
+            T::size(),
+            env,
+        )?;
+        let id = AnyPtr::new(self.offset()).id(env)?;
+
+        let offset = alloc(VIEW_SIZE, id, env)?;
+        write_u32(offset + BUFFER, buffer, env)?;
+        write_u32(offset + DATA_START, data_start, env)?;
+        write_u32(offset + BYTE_LENGTH, (end - begin) << T::ALIGN, env)?;
+
+        pin(offset, env)?;
+        Ok(Pinned::new(TypedArrayPtr::new(offset), env))
+    }
+}
+
+impl<T: Element + ValueType> TypedArrayPtr<T> {
+    /// Borrow the elements in the instance memory, without copying them.
+    ///
+    /// The view is invalidated if the memory grows, so it shouldn't be held
+    /// while calling functions of the instance.
+    pub fn view<'a>(&self, memory: &'a MemoryView) -> Result<WasmSlice<'a, T>, Error> {
+        let len = self.len(memory)?;
+        let data_start = read_field(self.offset(), DATA_START, memory)?;
+        WasmSlice::new(memory, u64::from(data_start), u64::from(len))
+            .map_err(|_| Error::out_of_bounds(data_start, memory))
+    }
+}
+
+impl<T> Clone for TypedArrayPtr<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for TypedArrayPtr<T> {}
+
+impl<T> Pointer for TypedArrayPtr<T> {
+    fn offset(&self) -> u32 {
+        self.0
+    }
+}
+
+unsafe impl<T> FromToNativeWasmType for TypedArrayPtr<T> {
+    type Native = i32;
+    fn to_native(self) -> Self::Native {
+        self.offset() as i32
+    }
+    fn from_native(n: Self::Native) -> Self {
+        Self::new(n as u32)
+    }
+}
+
+impl<T> Element for TypedArrayPtr<T> {
+    const ALIGN: u32 = 2;
+    const MANAGED: bool = true;
+
+    fn from_le_bytes(bytes: &[u8]) -> Self {
+        Self::new(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+    fn write_le_bytes(&self, bytes: &mut [u8]) {
+        bytes.copy_from_slice(&self.offset().to_le_bytes())
+    }
+}
+
+impl<T: Element> Read<Vec<T>> for TypedArrayPtr<T> {
+    fn read<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<Vec<T>, Error> {
+        let data_start = read_field(self.offset(), DATA_START, memory)?;
+        read_elements(data_start, self.len(memory)?, memory)
+    }
+
+    /// Size of the viewed data in bytes.
+    fn size<M: MemoryAccess + ?Sized>(&self, memory: &M) -> Result<u32, Error> {
+        read_field(self.offset(), BYTE_LENGTH, memory)
+    }
+}
+
+impl<T: Element> Write<Vec<T>> for TypedArrayPtr<T> {
+    fn alloc<R: Runtime>(value: &Vec<T>, env: &R) -> Result<Pinned<TypedArrayPtr<T>, R>, Error> {
+        let id = env.rtti()?.find_collection::<T, _>(ARRAYBUFFERVIEW, env)?;
+        let byte_length = byte_length::<T>(value.len())?;
+
+        // The backing buffer isn't referenced yet, pin it while the view
+        // is allocated in case a collection happens.
+        let buffer = alloc(byte_length, 0, env)?;
+        write_elements(buffer, value, env)?;
+        pin(buffer, env)?;
+        let offset = alloc(VIEW_SIZE, id, env);
+        unpin(buffer, env)?;
+        let offset = offset?;
+
+        write_u32(offset + BUFFER, buffer, env)?;
+        write_u32(offset + DATA_START, buffer, env)?;
+        write_u32(offset + BYTE_LENGTH, byte_length, env)?;
+
+        pin(offset, env)?;
+        Ok(Pinned::new(TypedArrayPtr::new(offset), env))
+    }
+
+    /// Write the elements in place if the length is the same, this also
+    /// modifies the buffer shared with other views.
+    fn write<R: Runtime>(
+        &mut self,
+        value: &Vec<T>,
+        env: &R,
+    ) -> Result<Box<TypedArrayPtr<T>>, Error> {
+        if self.len(env)? == u32::try_from(value.len())? {
+            let data_start = read_field(self.offset(), DATA_START, env)?;
+            write_elements(data_start, value, env)?;
+            Ok(Box::new(*self))
+        } else {
+            // unpin old ptr
+            unpin(self.offset(), env)?;
+
+            // collect
+            collect(env)?;
+
+            // alloc with new size
+            Ok(Box::new(TypedArrayPtr::alloc(value, env)?.leak()))
+        }
+    }
+
+    fn free<R: Runtime>(self, env: &R) -> Result<(), Error> {
+        release(self.offset(), env)
+    }
+}
@@ -0,0 +1,17 @@
+store_env! {
+    /// Environment of an instance running with wasmi, the `EnvMut` of wasmer.
+    ///
+    /// wasmi needs the store to access the memory or call a function, so the
+    /// environment borrows it until it's dropped. It's available in the meantime
+    /// with `WasmiEnv::store`.
+    ///
+    /// ```ignore
+    /// let instance = linker.instantiate(&mut store, &module)?.start(&mut store)?;
+    /// let env = WasmiEnv::new(&mut store, &instance)?;
+    ///
+    /// let str_ptr = StringPtr::alloc(&"hello".to_string(), &env)?;
+    /// assert_eq!(str_ptr.read(&env)?, "hello");
+    /// ```
+    WasmiEnv,
+    wasmi
+}
@@ -0,0 +1,17 @@
+store_env! {
+    /// Environment of an instance running with wasmtime, the `EnvMut` of wasmer.
+    ///
+    /// wasmtime needs the store to access the memory or call a function, so the
+    /// environment borrows it until it's dropped. It's available in the meantime
+    /// with `WasmtimeEnv::store`.
+    ///
+    /// ```ignore
+    /// let instance = linker.instantiate(&mut store, &module)?;
+    /// let env = WasmtimeEnv::new(&mut store, &instance)?;
+    ///
+    /// let str_ptr = StringPtr::alloc(&"hello".to_string(), &env)?;
+    /// assert_eq!(str_ptr.read(&env)?, "hello");
+    /// ```
+    WasmtimeEnv,
+    wasmtime
+}
@@ -0,0 +1,91 @@
+export function getInts(): Array<i32> {
+  return [1, 2, 3];
+}
+
+export function getStrings(): Array<string> {
+  return ["hello", "test"];
+}
+
+export function getNested(): Array<Array<i32>> {
+  return [[1, 2], [3]];
+}
+
+export function sumInts(a: Array<i32>): i32 {
+  let sum = 0;
+  for (let i = 0; i < a.length; i++) sum += a[i];
+  return sum;
+}
+
+export function getStaticInts(): StaticArray<i32> {
+  return [10, 20, 30];
+}
+
+export function sumStaticInts(a: StaticArray<i32>): i32 {
+  let sum = 0;
+  for (let i = 0; i < a.length; i++) sum += a[i];
+  return sum;
+}
+
+export function getFloats(): Float64Array {
+  const a = new Float64Array(3);
+  a[0] = 1.5; a[1] = 2.5; a[2] = 3.5;
+  return a;
+}
+
+export function getSubarray(): Int32Array {
+  const a = new Int32Array(5);
+  for (let i = 0; i < 5; i++) a[i] = i + 1;
+  return a.subarray(2, 4);
+}
+
+export function sumFloats(a: Float64Array): f64 {
+  let sum = 0.0;
+  for (let i = 0; i < a.length; i++) sum += a[i];
+  return sum;
+}
+
+// "two" and 5 are deleted to leave a removed entry in the tables
+export function getMap(): Map<string, i32> {
+  const map = new Map<string, i32>();
+  map.set("one", 1);
+  map.set("two", 2);
+  map.set("three", 3);
+  map.delete("two");
+  return map;
+}
+
+export function sumMap(map: Map<string, i32>): i32 {
+  const values = map.values();
+  let sum = 0;
+  for (let i = 0; i < values.length; i++) sum += values[i];
+  return sum;
+}
+
+export function getSet(): Set<u32> {
+  const set = new Set<u32>();
+  set.add(1);
+  set.add(5);
+  set.add(2);
+  set.add(3);
+  set.delete(5);
+  return set;
+}
+
+export function setHas(set: Set<u32>, value: u32): bool {
+  return set.has(value);
+}
+
+class Person {
+  name: string;
+  age: u8;
+  scores: Array<i32> | null;
+}
+
+export function getPerson(): Person {
+  return { name: "Alice", age: 30, scores: [1, 2, 3] };
+}
+
+export function personScore(p: Person): i32 {
+  const scores = p.scores;
+  return p.age + (scores ? sumInts(scores) : 0);
+}
@@ -0,0 +1,99 @@
+// Generated by as-ffi-bindings, do not edit.
+
+/// Exported functions of the `Collections` module.
+pub struct Collections {
+    get_ints: ::wasmer::TypedFunction<(), ::as_ffi_bindings::ArrayPtr<i32>>,
+    get_strings: ::wasmer::TypedFunction<(), ::as_ffi_bindings::ArrayPtr<::as_ffi_bindings::StringPtr>>,
+    get_nested: ::wasmer::TypedFunction<(), ::as_ffi_bindings::ArrayPtr<::as_ffi_bindings::ArrayPtr<i32>>>,
+    sum_ints: ::wasmer::TypedFunction<::as_ffi_bindings::ArrayPtr<i32>, i32>,
+    get_static_ints: ::wasmer::TypedFunction<(), ::as_ffi_bindings::StaticArrayPtr<i32>>,
+    get_floats: ::wasmer::TypedFunction<(), ::as_ffi_bindings::TypedArrayPtr<f64>>,
+    sum_floats: ::wasmer::TypedFunction<::as_ffi_bindings::TypedArrayPtr<f64>, f64>,
+    get_map: ::wasmer::TypedFunction<(), ::as_ffi_bindings::MapPtr<::as_ffi_bindings::StringPtr, i32>>,
+    get_set: ::wasmer::TypedFunction<(), ::as_ffi_bindings::SetPtr<u32>>,
+    set_has: ::wasmer::TypedFunction<(::as_ffi_bindings::SetPtr<u32>, u32), i32>,
+    get_person: ::wasmer::TypedFunction<(), ::as_ffi_bindings::AnyPtr>,
+    process: ::wasmer::TypedFunction<(::as_ffi_bindings::StringPtr, ::as_ffi_bindings::BufferPtr), ::as_ffi_bindings::StringPtr>,
+}
+
+impl Collections {
+    pub fn new(
+        instance: &::wasmer::Instance,
+        store: &impl ::wasmer::AsStoreRef,
+    ) -> Result<Self, ::wasmer::ExportError> {
+        Ok(Self {
+            get_ints: instance.exports.get_typed_function(store, "getInts")?,
+            get_strings: instance.exports.get_typed_function(store, "getStrings")?,
+            get_nested: instance.exports.get_typed_function(store, "getNested")?,
+            sum_ints: instance.exports.get_typed_function(store, "sumInts")?,
+            get_static_ints: instance.exports.get_typed_function(store, "getStaticInts")?,
+            get_floats: instance.exports.get_typed_function(store, "getFloats")?,
+            sum_floats: instance.exports.get_typed_function(store, "sumFloats")?,
+            get_map: instance.exports.get_typed_function(store, "getMap")?,
+            get_set: instance.exports.get_typed_function(store, "getSet")?,
+            set_has: instance.exports.get_typed_function(store, "setHas")?,
+            get_person: instance.exports.get_typed_function(store, "getPerson")?,
+            process: instance.exports.get_typed_function(store, "process")?,
+        })
+    }
+
+    /// `getInts(): ~lib/array/Array<i32>`
+    pub fn get_ints(&self, store: &mut impl ::wasmer::AsStoreMut) -> Result<::as_ffi_bindings::ArrayPtr<i32>, ::wasmer::RuntimeError> {
+        self.get_ints.call(store)
+    }
+
+    /// `getStrings(): ~lib/array/Array<~lib/string/String>`
+    pub fn get_strings(&self, store: &mut impl ::wasmer::AsStoreMut) -> Result<::as_ffi_bindings::ArrayPtr<::as_ffi_bindings::StringPtr>, ::wasmer::RuntimeError> {
+        self.get_strings.call(store)
+    }
+
+    /// `getNested(): ~lib/array/Array<~lib/array/Array<i32>>`
+    pub fn get_nested(&self, store: &mut impl ::wasmer::AsStoreMut) -> Result<::as_ffi_bindings::ArrayPtr<::as_ffi_bindings::ArrayPtr<i32>>, ::wasmer::RuntimeError> {
+        self.get_nested.call(store)
+    }
+
+    /// `sumInts(ints: ~lib/array/Array<i32>): i32`
+    pub fn sum_ints(&self, store: &mut impl ::wasmer::AsStoreMut, ints: ::as_ffi_bindings::ArrayPtr<i32>) -> Result<i32, ::wasmer::RuntimeError> {
+        self.sum_ints.call(store, ints)
+    }
+
+    /// `getStaticInts(): ~lib/staticarray/StaticArray<i32>`
+    pub fn get_static_ints(&self, store: &mut impl ::wasmer::AsStoreMut) -> Result<::as_ffi_bindings::StaticArrayPtr<i32>, ::wasmer::RuntimeError> {
+        self.get_static_ints.call(store)
+    }
+
+    /// `getFloats(): ~lib/typedarray/Float64Array`
+    pub fn get_floats(&self, store: &mut impl ::wasmer::AsStoreMut) -> Result<::as_ffi_bindings::TypedArrayPtr<f64>, ::wasmer::RuntimeError> {
+        self.get_floats.call(store)
+    }
+
+    /// `sumFloats(floats: ~lib/typedarray/Float64Array): f64`
+    pub fn sum_floats(&self, store: &mut impl ::wasmer::AsStoreMut, floats: ::as_ffi_bindings::TypedArrayPtr<f64>) -> Result<f64, ::wasmer::RuntimeError> {
+        self.sum_floats.call(store, floats)
+    }
+
+    /// `getMap(): ~lib/map/Map<~lib/string/String,i32>`
+    pub fn get_map(&self, store: &mut impl ::wasmer::AsStoreMut) -> Result<::as_ffi_bindings::MapPtr<::as_ffi_bindings::StringPtr, i32>, ::wasmer::RuntimeError> {
+        self.get_map.call(store)
+    }
+
+    /// `getSet(): ~lib/set/Set<u32>`
+    pub fn get_set(&self, store: &mut impl ::wasmer::AsStoreMut) -> Result<::as_ffi_bindings::SetPtr<u32>, ::wasmer::RuntimeError> {
+        self.get_set.call(store)
+    }
+
+    /// `setHas(set: ~lib/set/Set<u32>, value: u32): bool`
+    pub fn set_has(&self, store: &mut impl ::wasmer::AsStoreMut, set: ::as_ffi_bindings::SetPtr<u32>, value: u32) -> Result<bool, ::wasmer::RuntimeError> {
+        self.set_has.call(store, set, value).map(|value| value != 0)
+    }
+
+    /// `getPerson(): assembly/index/Person`
+    pub fn get_person(&self, store: &mut impl ::wasmer::AsStoreMut) -> Result<::as_ffi_bindings::AnyPtr, ::wasmer::RuntimeError> {
+        self.get_person.call(store)
+    }
+
+    /// `process(message: ~lib/string/String, data: ~lib/arraybuffer/ArrayBuffer | null): ~lib/string/String`
+    pub fn process(&self, store: &mut impl ::wasmer::AsStoreMut, message: ::as_ffi_bindings::StringPtr, data: ::as_ffi_bindings::BufferPtr) -> Result<::as_ffi_bindings::StringPtr, ::wasmer::RuntimeError> {
+        self.process.call(store, message, data)
+    }
+}
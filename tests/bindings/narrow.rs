@@ -0,0 +1,22 @@
+// Generated by as-ffi-bindings, do not edit.
+
+/// Exported functions of the `Narrow` module.
+pub struct Narrow {
+    negate: ::wasmer::TypedFunction<i32, i32>,
+}
+
+impl Narrow {
+    pub fn new(
+        instance: &::wasmer::Instance,
+        store: &impl ::wasmer::AsStoreRef,
+    ) -> Result<Self, ::wasmer::ExportError> {
+        Ok(Self {
+            negate: instance.exports.get_typed_function(store, "negate")?,
+        })
+    }
+
+    /// `negate(value: u8): i16`
+    pub fn negate(&self, store: &mut impl ::wasmer::AsStoreMut, value: u8) -> Result<i16, ::wasmer::RuntimeError> {
+        self.negate.call(store, value as i32).map(|value| value as i16)
+    }
+}
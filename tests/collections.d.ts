@@ -0,0 +1,90 @@
+/** Exported memory */
+export declare const memory: WebAssembly.Memory;
+/**
+ * assembly/index/getInts
+ * @returns `~lib/array/Array<i32>`
+ */
+export declare function getInts(): Array<number>;
+/**
+ * assembly/index/getStrings
+ * @returns `~lib/array/Array<~lib/string/String>`
+ */
+export declare function getStrings(): Array<string>;
+/**
+ * assembly/index/getNested
+ * @returns `~lib/array/Array<~lib/array/Array<i32>>`
+ */
+export declare function getNested(): Array<Array<number>>;
+/**
+ * assembly/index/sumInts
+ * @param ints `~lib/array/Array<i32>`
+ * @returns `i32`
+ */
+export declare function sumInts(ints: Array<number>): number;
+/**
+ * assembly/index/getStaticInts
+ * @returns `~lib/staticarray/StaticArray<i32>`
+ */
+export declare function getStaticInts(): ArrayLike<number>;
+/**
+ * assembly/index/getFloats
+ * @returns `~lib/typedarray/Float64Array`
+ */
+export declare function getFloats(): Float64Array;
+/**
+ * assembly/index/sumFloats
+ * @param floats `~lib/typedarray/Float64Array`
+ * @returns `f64`
+ */
+export declare function sumFloats(floats: Float64Array): number;
+/**
+ * assembly/index/getMap
+ * @returns `~lib/map/Map<~lib/string/String,i32>`
+ */
+export declare function getMap(): Map<string, number>;
+/**
+ * assembly/index/getSet
+ * @returns `~lib/set/Set<u32>`
+ */
+export declare function getSet(): Set<number>;
+/**
+ * assembly/index/setHas
+ * @param set `~lib/set/Set<u32>`
+ * @param value `u32`
+ * @returns `bool`
+ */
+export declare function setHas(set: Set<number>, value: number): boolean;
+/**
+ * assembly/index/getPerson
+ * @returns `assembly/index/Person`
+ */
+export declare function getPerson(): __Record12<never>;
+/**
+ * assembly/index/process
+ * @param message `~lib/string/String`
+ * @param data `~lib/arraybuffer/ArrayBuffer | null`
+ * @returns `~lib/string/String`
+ */
+export declare function process(message: string, data: ArrayBuffer | null): string;
+/**
+ * assembly/index/lastFailure
+ */
+export declare const lastFailure: {
+  /** @type `i32` */
+  get value(): number;
+  set value(value: number);
+};
+/**
+ * ~lib/rt/itcms/__new
+ * @param size `usize`
+ * @param id `u32`
+ * @returns `usize`
+ */
+export declare function __new(size: number, id: number): number;
+/** assembly/index/Person */
+declare interface __Record12<TOmittable> {
+  /** @type `~lib/string/String` */
+  name: string;
+  /** @type `u8` */
+  age: number;
+}
@@ -0,0 +1,51 @@
+(module
+ (import "env" "abort" (func $abort (param i32 i32 i32 i32)))
+ (import "env" "trace" (func $trace (param i32 i32 f64 f64 f64 f64 f64)))
+ (import "env" "seed" (func $seed (result f64)))
+ (import "env" "Date.now" (func $now (result f64)))
+ (memory $0 1)
+ (data (i32.const 16) "\n\00\00\00h\00e\00l\00l\00o\00")
+ (data (i32.const 36) "\0e\00\00\00f\00i\00l\00e\00.\00t\00s\00")
+ (export "memory" (memory $0))
+ (export "traceHello" (func $traceHello))
+ (export "getSeed" (func $getSeed))
+ (export "now" (func $getNow))
+ (export "fail" (func $fail))
+ (export "assert" (func $assert))
+ (func $traceHello
+  i32.const 20
+  i32.const 2
+  f64.const 1.5
+  f64.const 2
+  f64.const 0
+  f64.const 0
+  f64.const 0
+  call $trace
+ )
+ (func $getSeed (result f64)
+  call $seed
+ )
+ (func $getNow (result f64)
+  call $now
+ )
+ (func $fail (result i32)
+  i32.const 20
+  i32.const 40
+  i32.const 3
+  i32.const 7
+  call $abort
+  i32.const 1
+ )
+ (func $assert (param $ok i32)
+  local.get $ok
+  if
+   return
+  end
+  i32.const 20
+  i32.const 40
+  i32.const 3
+  i32.const 7
+  call $abort
+  unreachable
+ )
+)
@@ -0,0 +1,6 @@
+/**
+ * assembly/index/negate
+ * @param value `u8`
+ * @returns `i16`
+ */
+export declare function negate(value: number): number;
@@ -1,239 +1,1601 @@
-use as_ffi_bindings::{abort, AnyPtr, BufferPtr, Env, Read, StringPtr, Write};
-use std::{error::Error, sync::Mutex};
-use wasmer::{imports, Function, Instance, Module, Store};
+use as_ffi_bindings::{
+    abort, env_exports, host_function, imports, AbortError, AbortHandler, AnyPtr, AnyPtrExported,
+    ArrayPtr, AsClass, BufferPtr, Env, EnvMut, Error as AsError, MapPtr, MemoryAccess, ModuleInfo,
+    Read, Runtime, RuntimeVariant, SetPtr, StaticArrayPtr, StringPtr, Type, TypedArrayPtr,
+    Utf16Policy, Write,
+};
+use as_ffi_bindings_gen::{Bindings, ExportFn};
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    convert::TryFrom,
+    error::Error,
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use wasmer::{
+    imports, Function, FunctionEnv, Instance, Module, RuntimeError, Store, Type as WasmType,
+    TypedFunction, Value,
+};
+
+const RUNTIME_EXPORTED: &[u8] = include_bytes!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/runtime_exported.wat"
+));
+const SORT_BUFFER: &[u8] = include_bytes!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/sort_buffer.wasm"
+));
+const TEST_WAT: &[u8] = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_wat.wat"));
+const BUFFER: &[u8] = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/buffer.wasm"));
+const COLLECTIONS: &[u8] = include_bytes!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/collections.wat"
+));
+const MY_STRUCT: &[u8] =
+    include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/my_struct.wasm"));
+/// A runtime without the headers of the incremental one: `__new` bumps a
+/// pointer, `__pin` and `__unpin` count the pins and `__unpin` traps if
+/// nothing is pinned.
+const COUNTED_PINS: &[u8] = br#"(module
+    (memory (export "memory") 1)
+    (global $top (mut i32) (i32.const 16))
+    (global $pins (mut i32) (i32.const 0))
+    (func (export "__new") (param $size i32) (param $id i32) (result i32)
+        (local $ptr i32)
+        (local.set $ptr (i32.add (global.get $top) (i32.const 16)))
+        (i32.store (i32.sub (local.get $ptr) (i32.const 4)) (local.get $size))
+        (global.set $top
+            (i32.and (i32.add (i32.add (local.get $ptr) (local.get $size)) (i32.const 15))
+                (i32.const -16)))
+        (local.get $ptr))
+    (func (export "__pin") (param $ptr i32) (result i32)
+        (global.set $pins (i32.add (global.get $pins) (i32.const 1)))
+        (local.get $ptr))
+    (func (export "__unpin") (param $ptr i32)
+        (if (i32.eqz (global.get $pins)) (then unreachable))
+        (global.set $pins (i32.sub (global.get $pins) (i32.const 1))))
+    (func (export "__collect"))
+    (func (export "pins") (result i32) (global.get $pins)))"#;
+
+/// Call an export of the guest taking and returning `i32` values, whatever
+/// the runtime running it.
+type Call<'a> = dyn Fn(&str, &[i32]) -> Result<Option<i32>, Box<dyn Error>> + 'a;
+
+thread_local! {
+    /// Values given to the `index.print` import, by the thread of the test
+    static PRINTED: RefCell<Vec<i32>> = const { RefCell::new(Vec::new()) };
+}
+
+fn print(val: i32) {
+    PRINTED.with(|printed| printed.borrow_mut().push(val));
+}
+
+/// A runtime running the scenarios shared by the backends. The instances
+/// import `env.abort` and `index.print`.
+trait Backend {
+    type Env<'a>: Runtime;
+
+    fn run(
+        wasm_bytes: &[u8],
+        scenario: impl for<'a> FnOnce(&Self::Env<'a>, &Call) -> Result<(), Box<dyn Error>>,
+    ) -> Result<(), Box<dyn Error>>;
+}
+
+struct Wasmer;
+
+impl Backend for Wasmer {
+    type Env<'a> = EnvMut<'a>;
+
+    fn run(
+        wasm_bytes: &[u8],
+        scenario: impl for<'a> FnOnce(&EnvMut<'a>, &Call) -> Result<(), Box<dyn Error>>,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut store = Store::default();
+        let module = Module::new(&store, wasm_bytes)?;
+
+        let env = FunctionEnv::new(&mut store, Env::default());
+        let import_object = imports! {
+            "env" => {
+                "abort" => Function::new_typed_with_env(&mut store, &env, abort),
+            },
+            "index" => {
+                "print" => Function::new_typed(&mut store, print),
+            },
+        };
+
+        let instance = Instance::new(&mut store, &module, &import_object)?;
+
+        let env = EnvMut::new(env.into_mut(&mut store));
+        env.init(&instance)?;
+
+        let call = |name: &str, args: &[i32]| -> Result<Option<i32>, Box<dyn Error>> {
+            let args: Vec<Value> = args.iter().map(|arg| Value::I32(*arg)).collect();
+            let results = instance
+                .exports
+                .get_function(name)?
+                .call(&mut *env.store(), &args)?;
+            Ok(results.first().and_then(Value::i32))
+        };
+        scenario(&env, &call)
+    }
+}
+
+#[cfg(feature = "wasmtime")]
+struct Wasmtime;
+
+#[cfg(feature = "wasmtime")]
+impl Backend for Wasmtime {
+    type Env<'a> = as_ffi_bindings::WasmtimeEnv<'a, ()>;
+
+    fn run(
+        wasm_bytes: &[u8],
+        scenario: impl for<'a> FnOnce(&Self::Env<'a>, &Call) -> Result<(), Box<dyn Error>>,
+    ) -> Result<(), Box<dyn Error>> {
+        use wasmtime::Val;
+
+        let engine = wasmtime::Engine::default();
+        let module = wasmtime::Module::new(&engine, wasm_bytes)?;
+        let mut linker = wasmtime::Linker::new(&engine);
+        linker.func_wrap("env", "abort", |_: i32, _: i32, _: i32, _: i32| {})?;
+        linker.func_wrap("index", "print", print)?;
+        let mut store = wasmtime::Store::new(&engine, ());
+        let instance = linker.instantiate(&mut store, &module)?;
+
+        let env = as_ffi_bindings::WasmtimeEnv::new(&mut store, &instance)?;
+
+        let call = |name: &str, args: &[i32]| -> Result<Option<i32>, Box<dyn Error>> {
+            let mut store = env.store();
+            let func = instance.get_func(&mut *store, name).ok_or(name)?;
+            let args: Vec<Val> = args.iter().map(|arg| Val::I32(*arg)).collect();
+            let mut results = vec![Val::I32(0); func.ty(&*store).results().len()];
+            func.call(&mut *store, &args, &mut results)?;
+            Ok(results.first().and_then(Val::i32))
+        };
+        scenario(&env, &call)
+    }
+}
+
+#[cfg(feature = "wasmi")]
+struct Wasmi;
+
+#[cfg(feature = "wasmi")]
+impl Backend for Wasmi {
+    type Env<'a> = as_ffi_bindings::WasmiEnv<'a, ()>;
+
+    fn run(
+        wasm_bytes: &[u8],
+        scenario: impl for<'a> FnOnce(&Self::Env<'a>, &Call) -> Result<(), Box<dyn Error>>,
+    ) -> Result<(), Box<dyn Error>> {
+        use wasmi::Val;
+
+        // wasmi only reads the binary format
+        let wasm_bytes = wat::parse_bytes(wasm_bytes)?;
+        let engine = wasmi::Engine::default();
+        let module = wasmi::Module::new(&engine, &wasm_bytes[..])?;
+        let mut linker = wasmi::Linker::new(&engine);
+        linker.func_wrap("env", "abort", |_: i32, _: i32, _: i32, _: i32| {})?;
+        linker.func_wrap("index", "print", print)?;
+        let mut store = wasmi::Store::new(&engine, ());
+        let instance = linker.instantiate(&mut store, &module)?.start(&mut store)?;
+
+        let env = as_ffi_bindings::WasmiEnv::new(&mut store, &instance)?;
+
+        let call = |name: &str, args: &[i32]| -> Result<Option<i32>, Box<dyn Error>> {
+            let mut store = env.store();
+            let func = instance.get_func(&*store, name).ok_or(name)?;
+            let args: Vec<Val> = args.iter().map(|arg| Val::I32(*arg)).collect();
+            let mut results = vec![Val::I32(0); func.ty(&*store).results().len()];
+            func.call(&mut *store, &args, &mut results)?;
+            Ok(results.first().and_then(Val::i32))
+        };
+        scenario(&env, &call)
+    }
+}
+
+/// The scenarios shared by the backends, run on each of them.
+fn shared_scenarios<B: Backend>() -> Result<(), Box<dyn Error>> {
+    B::run(TEST_WAT, |env, call| read_strings(env, call))?;
+    B::run(RUNTIME_EXPORTED, |env, call| alloc_strings(env, call))?;
+    B::run(RUNTIME_EXPORTED, |env, call| write_strings(env, call))?;
+    B::run(RUNTIME_EXPORTED, |env, call| pin_guards(env, call))?;
+    B::run(COUNTED_PINS, |env, call| host_pins(env, call))?;
+    B::run(RUNTIME_EXPORTED, |env, call| any_types(env, call))?;
+    B::run(BUFFER, |env, call| read_buffers(env, call))?;
+    B::run(SORT_BUFFER, |env, call| sort_buffers(env, call))?;
+    B::run(SORT_BUFFER, |env, call| free_sorted_buffers(env, call))?;
+    B::run(COLLECTIONS, |env, call| read_arrays(env, call))?;
+    B::run(COLLECTIONS, |env, call| alloc_arrays(env, call))?;
+    B::run(COLLECTIONS, |env, call| maps_and_sets(env, call))?;
+    read_write_any::<B>()
+}
 
 #[test]
-fn read_strings() -> Result<(), Box<dyn Error>> {
-    let wasm_bytes = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_wat.wat"));
-    let store = Store::default();
+fn wasmer_backend() -> Result<(), Box<dyn Error>> {
+    shared_scenarios::<Wasmer>()
+}
+
+#[cfg(feature = "wasmtime")]
+#[test]
+fn wasmtime_backend() -> Result<(), Box<dyn Error>> {
+    shared_scenarios::<Wasmtime>()
+}
+
+#[cfg(feature = "wasmi")]
+#[test]
+fn wasmi_backend() -> Result<(), Box<dyn Error>> {
+    shared_scenarios::<Wasmi>()
+}
+
+fn alloc_strings<R: Runtime>(env: &R, call: &Call) -> Result<(), Box<dyn Error>> {
+    let str_ptr = StringPtr::new(call("getString", &[])?.unwrap_or_default() as u32);
+    let string = str_ptr.read(env)?;
+
+    assert_eq!(string, "hello test");
+
+    let str_ptr_2 = StringPtr::alloc(&"hello return".to_string(), env)?;
+    let string = str_ptr_2.read(env)?;
+    assert_eq!(string, "hello return");
+
+    Ok(())
+}
+
+fn write_strings<R: Runtime>(env: &R, call: &Call) -> Result<(), Box<dyn Error>> {
+    let mut str_ptr = StringPtr::new(call("getString", &[])?.unwrap_or_default() as u32);
+    let string = str_ptr.read(env)?;
+
+    assert_eq!(string, "hello test");
+
+    str_ptr.write(&"hallo tast".to_string(), env)?;
+
+    let str_ptr_2 = StringPtr::new(call("getString", &[])?.unwrap_or_default() as u32);
+    let string = str_ptr_2.read(env)?;
+
+    assert_eq!(string, "hallo tast");
+    Ok(())
+}
+
+fn sort_buffers<R: Runtime>(env: &R, call: &Call) -> Result<(), Box<dyn Error>> {
+    let input: Vec<u8> = vec![0x03, 0x02, 0x08, 0x00, 0x04, 0x01, 0x05];
+    let buffer_ptr = BufferPtr::alloc(&input, env)?;
+    call("sortBuffer", &[buffer_ptr.offset() as i32])?;
+    let sorted = buffer_ptr.read(env)?;
+
+    let expected: Vec<u8> = vec![0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x08];
+
+    assert_eq!(sorted, expected);
+
+    // Now checking with odd size
+    let input: Vec<u8> = vec![0x03, 0x02, 0x00, 0x01, 0x09];
+    let buffer_ptr = BufferPtr::alloc(&input, env)?;
+    assert_eq!(buffer_ptr.size(env)?, 5);
+
+    Ok(())
+}
+
+fn pin_guards<R: Runtime>(env: &R, _: &Call) -> Result<(), Box<dyn Error>> {
+    // color of the object in the incremental GC header, 3 while pinned
+    let color = |offset: u32| -> Result<u32, AsError> {
+        let mut header = [0; 4];
+        env.read_bytes(offset - 16, &mut header)?;
+        Ok(u32::from_le_bytes(header) & 3)
+    };
+
+    let offset = {
+        let str_ptr = StringPtr::alloc(&"hello".to_string(), env)?;
+        assert_eq!(color(str_ptr.offset())?, 3);
+        assert!(env.pinned(str_ptr.offset())?);
+        str_ptr.offset()
+    };
+    assert_ne!(color(offset)?, 3);
+    assert!(!env.pinned(offset)?);
+
+    let buffer = BufferPtr::alloc(&vec![1, 2, 3], env)?;
+    assert_eq!(color(buffer.offset())?, 3);
+    let buffer = buffer.into_inner()?;
+    assert_ne!(color(buffer.offset())?, 3);
+
+    let leaked = StringPtr::alloc(&"leaked".to_string(), env)?.leak();
+    assert_eq!(color(leaked.offset())?, 3);
+    leaked.free(env)?;
+    Ok(())
+}
+
+fn host_pins<R: Runtime>(env: &R, call: &Call) -> Result<(), Box<dyn Error>> {
+    // the runtime has no header to tell if an object is pinned, `free` only
+    // unpins the objects the host didn't unpin yet
+    let leaked = StringPtr::alloc(&"leaked".to_string(), env)?.leak();
+    assert!(env.pinned(leaked.offset())?);
+    assert_eq!(call("pins", &[])?, Some(1));
+    leaked.free(env)?;
+    assert!(!env.pinned(leaked.offset())?);
+    assert_eq!(call("pins", &[])?, Some(0));
+
+    let buffer = BufferPtr::alloc(&vec![1, 2, 3], env)?.into_inner()?;
+    assert!(!env.pinned(buffer.offset())?);
+    buffer.free(env)?;
+    assert_eq!(call("pins", &[])?, Some(0));
+    Ok(())
+}
+
+fn free_sorted_buffers<R: Runtime>(env: &R, call: &Call) -> Result<(), Box<dyn Error>> {
+    let input: Vec<u8> = (0..=255).rev().collect();
+    let first = BufferPtr::alloc(&input, env)?.leak();
+    first.free(env)?;
+    let memory_size = env.data_size();
+
+    // 4MB are allocated in total, the memory would grow if they weren't
+    // reclaimed
+    for _ in 0..1000 {
+        let buffer_ptr = BufferPtr::alloc(&input.repeat(16), env)?.leak();
+        call("sortBuffer", &[buffer_ptr.offset() as i32])?;
+        assert_eq!(buffer_ptr.read(env)?[..16], [0; 16]);
+        buffer_ptr.free(env)?;
+    }
+    assert_eq!(env.data_size(), memory_size);
+
+    // the freed memory is reused
+    let buffer_ptr = BufferPtr::alloc(&input, env)?.into_inner()?;
+    assert_eq!(buffer_ptr.offset(), first.offset());
+    // already unpinned
+    buffer_ptr.free(env)?;
+    Ok(())
+}
+
+fn read_strings<R: Runtime>(env: &R, call: &Call) -> Result<(), Box<dyn Error>> {
+    let str_ptr = StringPtr::new(call("getString", &[])?.unwrap_or_default() as u32);
+    assert_eq!(str_ptr.read(env)?, "$¢ह한𝌆");
+    Ok(())
+}
+
+fn read_buffers<R: Runtime>(env: &R, call: &Call) -> Result<(), Box<dyn Error>> {
+    let buffer_ptr = BufferPtr::new(call("get_buffer", &[])?.unwrap_or_default() as u32);
+    let expected: Vec<u8> = vec![0x01, 0x03, 0x03, 0xFF];
+    assert_eq!(buffer_ptr.read(env)?, expected);
+    Ok(())
+}
+
+fn any_types<R: Runtime>(env: &R, _: &Call) -> Result<(), Box<dyn Error>> {
+    let buffer_ptr = BufferPtr::alloc(&vec![0x01, 0x03, 0x03, 0xFF], env)?;
+    match AnyPtr::new(buffer_ptr.offset()).to_type(env)? {
+        Type::Buffer(ptr) => assert_eq!(ptr.offset(), buffer_ptr.offset()),
+        _ => panic!("expected a buffer"),
+    }
+
+    // written in place while the size in bytes is the same, the string
+    // keeps its class
+    let str_ptr = StringPtr::alloc(&"abc".to_string(), env)?;
+    let mut any_ptr = AnyPtr::new(str_ptr.offset());
+    any_ptr = *any_ptr.write(
+        &"xyz".encode_utf16().flat_map(u16::to_le_bytes).collect(),
+        env,
+    )?;
+    assert_eq!(any_ptr.offset(), str_ptr.offset());
+    assert!(matches!(any_ptr.to_type(env)?, Type::String(_)));
+    assert_eq!(str_ptr.read(env)?, "xyz");
+
+    let outside = u32::try_from(env.data_size())? + 4;
+    match BufferPtr::new(outside).read(env) {
+        Err(AsError::OutOfBounds { offset, .. }) => assert_eq!(offset, outside),
+        _ => panic!("expected an out of bounds offset"),
+    }
+    Ok(())
+}
+
+fn read_arrays<R: Runtime>(env: &R, call: &Call) -> Result<(), Box<dyn Error>> {
+    let ints = ArrayPtr::<i32>::new(call("getInts", &[])?.unwrap_or_default() as u32);
+    assert_eq!(ints.len(env)?, 3);
+    assert_eq!(ints.read(env)?, vec![1, 2, 3]);
+
+    let strings = ArrayPtr::<StringPtr>::new(call("getStrings", &[])?.unwrap_or_default() as u32);
+    let strings = strings
+        .read(env)?
+        .iter()
+        .map(|ptr| ptr.read(env))
+        .collect::<Result<Vec<String>, _>>()?;
+    assert_eq!(strings, vec!["hello", "test"]);
+
+    let nested = ArrayPtr::<ArrayPtr<i32>>::new(call("getNested", &[])?.unwrap_or_default() as u32);
+    let nested = nested
+        .read(env)?
+        .iter()
+        .map(|ptr| ptr.read(env))
+        .collect::<Result<Vec<Vec<i32>>, _>>()?;
+    assert_eq!(nested, vec![vec![1, 2], vec![3]]);
+    Ok(())
+}
+
+fn alloc_arrays<R: Runtime>(env: &R, call: &Call) -> Result<(), Box<dyn Error>> {
+    let sum_ints = |ints: &ArrayPtr<i32>| call("sumInts", &[ints.offset() as i32]);
+
+    let mut ints = ArrayPtr::alloc(&vec![4, 5, 6], env)?;
+    assert_eq!(sum_ints(&ints)?, Some(15));
+
+    ints.write(&vec![7, 8, 9])?;
+    assert_eq!(sum_ints(&ints)?, Some(24));
+
+    // the guard holds the reallocated array
+    ints.write(&vec![1, 2, 3, 4])?;
+    assert_eq!(ints.read(env)?, vec![1, 2, 3, 4]);
+    assert_eq!(ints.size(env)?, 16);
+    assert_eq!(sum_ints(&ints)?, Some(10));
+
+    let hello = StringPtr::alloc(&"hello".to_string(), env)?;
+    let world = StringPtr::alloc(&"world".to_string(), env)?;
+    let strings = ArrayPtr::alloc(&vec![*hello, *world], env)?;
+    hello.free()?;
+    world.free()?;
+
+    // the strings are still referenced by the pinned array
+    let strings = strings
+        .read(env)?
+        .iter()
+        .map(|ptr| ptr.read(env))
+        .collect::<Result<Vec<String>, _>>()?;
+    assert_eq!(strings, vec!["hello", "world"]);
+
+    match ArrayPtr::<f64>::alloc(&vec![1.0], env) {
+        Err(AsError::TypeNotFound(_)) => {}
+        _ => panic!("Array<f64> isn't used by the module"),
+    }
+    Ok(())
+}
+
+fn maps_and_sets<R: Runtime>(env: &R, call: &Call) -> Result<(), Box<dyn Error>> {
+    let set_has = |set: &SetPtr<u32>, value: i32| call("setHas", &[set.offset() as i32, value]);
+
+    // the entry "two" was deleted
+    let map = MapPtr::<StringPtr, i32>::new(call("getMap", &[])?.unwrap_or_default() as u32);
+    assert_eq!(map.len(env)?, 2);
+    let entries = map
+        .read(env)?
+        .into_iter()
+        .map(|(key, value)| Ok((key.read(env)?, value)))
+        .collect::<Result<Vec<_>, AsError>>()?;
+    assert_eq!(
+        entries,
+        vec![("one".to_string(), 1), ("three".to_string(), 3)]
+    );
+    assert!(matches!(
+        AnyPtr::new(map.offset()).to_type(env)?,
+        Type::Map(_)
+    ));
+
+    // the value 5 was deleted
+    let set = SetPtr::<u32>::new(call("getSet", &[])?.unwrap_or_default() as u32);
+    assert_eq!(set.read(env)?, vec![1, 2, 3]);
+    assert_eq!(
+        set.to_hash_set(env)?,
+        [1, 2, 3].into_iter().collect::<HashSet<_>>()
+    );
+    assert_eq!(set_has(&set, 2)?, Some(1));
+    assert_eq!(set_has(&set, 5)?, Some(0));
+
+    let keys = ["a", "b", "c", "d", "e"]
+        .iter()
+        .map(|key| StringPtr::alloc(&key.to_string(), env))
+        .collect::<Result<Vec<_>, _>>()?;
+    let map = MapPtr::alloc(&keys.iter().map(|key| **key).zip(1..).collect(), env)?;
+    // the keys are only referenced by the map
+    for key in keys {
+        key.free()?;
+    }
+    assert_eq!(call("sumMap", &[map.offset() as i32])?, Some(15));
+    assert_eq!(map.len(env)?, 5);
+    let (key, value) = map.read(env)?[4];
+    assert_eq!((key.read(env)?, value), ("e".to_string(), 5));
+
+    // the guest finds the values with its own hash
+    let set = SetPtr::alloc(&(0..20).map(|i| i * 7).collect(), env)?;
+    assert_eq!(set.len(env)?, 20);
+    for i in 0..140 {
+        assert_eq!(set_has(&set, i)?, Some((i % 7 == 0) as i32));
+    }
+    Ok(())
+}
+
+/// Export an object from an instance and import it in another one.
+fn read_write_any<B: Backend>() -> Result<(), Box<dyn Error>> {
+    PRINTED.with(|printed| printed.borrow_mut().clear());
+    let mut exported = None;
+    B::run(MY_STRUCT, |env, call| {
+        let ptr = AnyPtr::new(call("get_struct", &[])?.unwrap_or_default() as u32);
+        exported = Some(ptr.export(env)?);
+        Ok(())
+    })?;
+    let exported = exported.expect("exported object");
+    B::run(MY_STRUCT, |env, call| {
+        let ptr = AnyPtr::import(&exported, env)?.offset();
+        assert_eq!(exported.id, AnyPtr::new(ptr).export(env)?.id);
+        call("dump", &[ptr as i32])?;
+        Ok(())
+    })?;
+
+    assert_eq!(PRINTED.with(|printed| printed.take()), vec![12, 13, 12, 13]);
+    Ok(())
+}
+
+#[test]
+fn free_any() -> Result<(), Box<dyn Error>> {
+    let wasm_bytes = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/my_struct.wasm"));
+    let mut store = Store::default();
     let module = Module::new(&store, wasm_bytes)?;
+
+    let env = FunctionEnv::new(&mut store, Env::default());
     let import_object = imports! {
         "env" => {
-            "abort" => Function::new_native_with_env(&store, Env::default(), abort),
+            "abort" => Function::new_typed_with_env(&mut store, &env, abort),
         },
+        "index" => {
+            "print" => Function::new_typed(&mut store, |_: i32| {}),
+        }
     };
 
-    let instance = Instance::new(&module, &import_object)?;
-    let memory = instance.exports.get_memory("memory").expect("get memory");
+    let instance = Instance::new(&mut store, &module, &import_object)?;
 
-    let get_string = instance
-        .exports
-        .get_native_function::<(), StringPtr>("getString")?;
+    let env = EnvMut::new(env.into_mut(&mut store));
+    env.init(&instance)?;
 
-    let str_ptr = get_string.call()?;
-    let string = str_ptr.read(memory)?;
+    let mut exported = env.call::<_, AnyPtr>("get_struct", ())?.export(&env)?;
+    exported.content.resize(4096, 0);
 
-    assert_eq!(string, "$¢ह한𝌆");
+    let first = AnyPtr::import(&exported, &env)?.leak();
+    AnyPtr::new(first.offset()).free(&env)?;
+    let memory_size = env.data_size();
 
+    for _ in 0..1000 {
+        let ptr = AnyPtr::alloc(&exported.content, &env)?.leak();
+        assert_eq!(ptr.read(&env)?, exported.content);
+        ptr.free(&env)?;
+    }
+    assert_eq!(env.data_size(), memory_size);
     Ok(())
 }
 
 #[test]
-fn read_alloc_strings() -> Result<(), Box<dyn Error>> {
+fn typed_errors() -> Result<(), Box<dyn Error>> {
+    let wasm_bytes = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/buffer.wasm"));
+    let mut store = Store::default();
+    let module = Module::new(&store, wasm_bytes)?;
+
+    let uninitialized = FunctionEnv::new(&mut store, Env::default());
+    match StringPtr::alloc(
+        &"hello".to_string(),
+        &EnvMut::new(uninitialized.into_mut(&mut store)),
+    ) {
+        Err(AsError::MissingExport(_)) => {}
+        _ => panic!("expected a missing export"),
+    }
+
+    let env = FunctionEnv::new(&mut store, Env::default());
+    let import_object = imports! {
+        "env" => {
+            "abort" => Function::new_typed_with_env(&mut store, &env, abort),
+        },
+    };
+
+    let instance = Instance::new(&mut store, &module, &import_object)?;
+
+    let env = EnvMut::new(env.into_mut(&mut store));
+    env.init(&instance)?;
+
+    // buffer.wasm doesn't export the runtime
+    match BufferPtr::alloc(&vec![0x01], &env) {
+        Err(AsError::MissingExport(name)) => assert_eq!(name, "__new"),
+        _ => panic!("expected a missing export"),
+    }
+
+    match StringPtr::new(0).read(&env) {
+        Err(AsError::OutOfBounds { offset, .. }) => assert_eq!(offset, 0),
+        _ => panic!("expected an out of bounds offset"),
+    }
+
+    let outside = u32::try_from(env.data_size())? + 4;
+    match BufferPtr::new(outside).read(&env) {
+        Err(AsError::OutOfBounds {
+            offset,
+            memory_size,
+        }) => {
+            assert_eq!(offset, outside);
+            assert_eq!(memory_size, env.data_size());
+        }
+        _ => panic!("expected an out of bounds offset"),
+    }
+    Ok(())
+}
+
+#[test]
+fn rtti_types() -> Result<(), Box<dyn Error>> {
     let wasm_bytes = include_bytes!(concat!(
         env!("CARGO_MANIFEST_DIR"),
-        "/tests/runtime_exported.wat"
+        "/tests/sort_buffer.wasm"
     ));
-    let store = Store::default();
+    let mut store = Store::default();
     let module = Module::new(&store, wasm_bytes)?;
 
+    let env = FunctionEnv::new(&mut store, Env::default());
     let import_object = imports! {
         "env" => {
-            "abort" => Function::new_native_with_env(&store, Env::default(), abort),
+            "abort" => Function::new_typed_with_env(&mut store, &env, abort),
         },
     };
 
-    let instance = Instance::new(&module, &import_object)?;
-    let memory = instance.exports.get_memory("memory").expect("get memory");
+    let instance = Instance::new(&mut store, &module, &import_object)?;
 
-    let mut env = Env::default();
+    let env = EnvMut::new(env.into_mut(&mut store));
     env.init(&instance)?;
 
-    let get_string = instance
-        .exports
-        .get_native_function::<(), StringPtr>("getString")?;
-
-    let str_ptr = get_string.call()?;
-    let string = str_ptr.read(memory)?;
+    let rtti = env.rtti()?;
+    assert_eq!(rtti.count(&env)?, 5);
+    let string = rtti.type_info(1, &env)?;
+    assert!(string.is_pointerfree());
+    assert_eq!(string.base_id(), None);
 
-    assert_eq!(string, "hello test");
-
-    let str_ptr_2 = StringPtr::alloc(&"hello return".to_string(), &env)?;
-    let string = str_ptr_2.read(memory)?;
-    assert_eq!(string, "hello return");
+    // sortBuffer takes a StaticArray<u8>
+    let static_array = rtti.type_info(3, &env)?;
+    assert!(static_array.is_static_array());
+    assert!(static_array.is_pointerfree());
+    assert!(!static_array.is_value_signed());
+    assert_eq!(static_array.value_align(), Some(0));
+    assert!(matches!(
+        rtti.type_info(5, &env),
+        Err(AsError::UnknownType(5))
+    ));
 
+    let exported = AnyPtrExported {
+        id: 3,
+        content: vec![0x02, 0x01],
+    };
+    let ptr = AnyPtr::import(&exported, &env)?;
+    assert!(matches!(
+        AnyPtr::new(ptr.offset()).to_type(&env)?,
+        Type::StaticArray(_)
+    ));
+    let ptr = StringPtr::alloc(&"hello".to_string(), &env)?;
+    assert!(matches!(
+        AnyPtr::new(ptr.offset()).to_type(&env)?,
+        Type::String(_)
+    ));
     Ok(())
 }
 
 #[test]
-fn read_write_strings() -> Result<(), Box<dyn Error>> {
+fn static_arrays() -> Result<(), Box<dyn Error>> {
     let wasm_bytes = include_bytes!(concat!(
         env!("CARGO_MANIFEST_DIR"),
-        "/tests/runtime_exported.wat"
+        "/tests/sort_buffer.wasm"
     ));
-    let store = Store::default();
+    let mut store = Store::default();
     let module = Module::new(&store, wasm_bytes)?;
 
+    let env = FunctionEnv::new(&mut store, Env::default());
     let import_object = imports! {
         "env" => {
-            "abort" => Function::new_native_with_env(&store, Env::default(), abort),
+            "abort" => Function::new_typed_with_env(&mut store, &env, abort),
         },
     };
 
-    let instance = Instance::new(&module, &import_object)?;
-    let memory = instance.exports.get_memory("memory").expect("get memory");
+    let instance = Instance::new(&mut store, &module, &import_object)?;
 
-    let mut env = Env::default();
+    let env = EnvMut::new(env.into_mut(&mut store));
     env.init(&instance)?;
 
-    let get_string = instance
+    let sort_buffer = instance
         .exports
-        .get_native_function::<(), StringPtr>("getString")?;
-
-    let mut str_ptr = get_string.call()?;
-    let string = str_ptr.read(memory)?;
-
-    assert_eq!(string, "hello test");
+        .get_typed_function::<StaticArrayPtr<u8>, ()>(&*env.store(), "sortBuffer")?;
 
-    str_ptr.write(&"hallo tast".to_string(), &env)?;
-
-    let str_ptr_2 = get_string.call()?;
-    let string = str_ptr_2.read(memory)?;
+    let input: Vec<u8> = vec![0x03, 0x02, 0x08, 0x00, 0x04, 0x01, 0x05];
+    let ptr = StaticArrayPtr::alloc(&input, &env)?;
+    assert_eq!(AnyPtr::new(ptr.offset()).id(&env)?, 3);
+    sort_buffer.call(&mut *env.store(), *ptr)?;
+    assert_eq!(
+        ptr.read(&env)?,
+        vec![0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x08]
+    );
 
-    assert_eq!(string, "hallo tast");
+    // modify the guest memory in place
+    {
+        let store = env.store();
+        let memory = instance.exports.get_memory("memory")?.view(&*store);
+        let view = ptr.view(&memory)?;
+        assert_eq!(view.len(), 7);
+        assert_eq!(view.index(6).read()?, 0x08);
+        view.index(0).write(0x09)?;
+    }
+    sort_buffer.call(&mut *env.store(), *ptr)?;
+    assert_eq!(
+        ptr.read(&env)?,
+        vec![0x01, 0x02, 0x03, 0x04, 0x05, 0x08, 0x09]
+    );
     Ok(())
 }
 
 #[test]
-fn read_buffers() -> Result<(), Box<dyn Error>> {
-    let wasm_bytes = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/buffer.wasm"));
-    let store = Store::default();
+fn static_arrays_i32() -> Result<(), Box<dyn Error>> {
+    let wasm_bytes = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/collections.wat"
+    ));
+    let mut store = Store::default();
     let module = Module::new(&store, wasm_bytes)?;
+
+    let env = FunctionEnv::new(&mut store, Env::default());
     let import_object = imports! {
         "env" => {
-            "abort" => Function::new_native_with_env(&store, Env::default(), abort),
+            "abort" => Function::new_typed_with_env(&mut store, &env, abort),
         },
     };
 
-    let instance = Instance::new(&module, &import_object)?;
-    let memory = instance.exports.get_memory("memory").expect("get memory");
+    let instance = Instance::new(&mut store, &module, &import_object)?;
+
+    let env = EnvMut::new(env.into_mut(&mut store));
+    env.init(&instance)?;
 
-    let get_string = instance
+    let get_static_ints = instance
         .exports
-        .get_native_function::<(), BufferPtr>("get_buffer")?;
+        .get_typed_function::<(), StaticArrayPtr<i32>>(&*env.store(), "getStaticInts")?;
+    let sum_static_ints = instance
+        .exports
+        .get_typed_function::<StaticArrayPtr<i32>, i32>(&*env.store(), "sumStaticInts")?;
 
-    let str_ptr = get_string.call()?;
-    let vec = str_ptr.read(memory)?;
-    let expected: Vec<u8> = vec![0x01, 0x03, 0x03, 0xFF];
-    assert_eq!(vec, expected);
+    let ints = get_static_ints.call(&mut *env.store())?;
+    assert_eq!(ints.read(&env)?, vec![10, 20, 30]);
+    {
+        let store = env.store();
+        let memory = instance.exports.get_memory("memory")?.view(&*store);
+        assert_eq!(ints.view(&memory)?.read_to_vec()?.iter().sum::<i32>(), 60);
+    }
+
+    let ints = StaticArrayPtr::alloc(&vec![-1, 2, 3, 4], &env)?;
+    assert_eq!(ints.len(&env)?, 4);
+    assert_eq!(ints.size(&env)?, 16);
+    assert_eq!(sum_static_ints.call(&mut *env.store(), *ints)?, 8);
+    assert!(matches!(
+        AnyPtr::new(ints.offset()).to_type(&env)?,
+        Type::StaticArray(_)
+    ));
     Ok(())
 }
 
 #[test]
-fn alloc_buffer() -> Result<(), Box<dyn Error>> {
+fn typed_arrays() -> Result<(), Box<dyn Error>> {
     let wasm_bytes = include_bytes!(concat!(
         env!("CARGO_MANIFEST_DIR"),
-        "/tests/sort_buffer.wasm"
+        "/tests/collections.wat"
     ));
-    let store = Store::default();
+    let mut store = Store::default();
     let module = Module::new(&store, wasm_bytes)?;
 
+    let env = FunctionEnv::new(&mut store, Env::default());
     let import_object = imports! {
         "env" => {
-            "abort" => Function::new_native_with_env(&store, Env::default(), abort),
+            "abort" => Function::new_typed_with_env(&mut store, &env, abort),
         },
     };
 
-    let instance = Instance::new(&module, &import_object)?;
-    let memory = instance.exports.get_memory("memory").expect("get memory");
+    let instance = Instance::new(&mut store, &module, &import_object)?;
 
-    let mut env = Env::default();
-    env.init(&instance).unwrap();
+    let env = EnvMut::new(env.into_mut(&mut store));
+    env.init(&instance)?;
 
-    let sort_buffer = instance
+    let get_floats = instance
         .exports
-        .get_native_function::<i32, ()>("sortBuffer")?;
+        .get_typed_function::<(), TypedArrayPtr<f64>>(&*env.store(), "getFloats")?;
+    let sum_floats = instance
+        .exports
+        .get_typed_function::<TypedArrayPtr<f64>, f64>(&*env.store(), "sumFloats")?;
+    let get_subarray = instance
+        .exports
+        .get_typed_function::<(), TypedArrayPtr<i32>>(&*env.store(), "getSubarray")?;
 
-    let input: Vec<u8> = vec![0x03, 0x02, 0x08, 0x00, 0x04, 0x01, 0x05];
-    let buffer_ptr = BufferPtr::alloc(&input, &env)?;
-    sort_buffer.call(buffer_ptr.offset() as i32)?;
-    let sorted = buffer_ptr.read(memory)?;
+    let floats = get_floats.call(&mut *env.store())?;
+    assert_eq!(floats.read(&env)?, vec![1.5, 2.5, 3.5]);
+    assert!(matches!(
+        AnyPtr::new(floats.offset()).to_type(&env)?,
+        Type::TypedArray(_)
+    ));
 
-    let expected: Vec<u8> = vec![0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x08];
+    // a view on the elements 2 and 3 of the buffer [1, 2, 3, 4, 5]
+    let ints = get_subarray.call(&mut *env.store())?;
+    assert_eq!(ints.len(&env)?, 2);
+    assert_eq!(ints.byte_offset(&env)?, 8);
+    assert_eq!(ints.read(&env)?, vec![3, 4]);
+    assert_eq!(ints.buffer(&env)?.size(&env)?, 20);
 
-    assert_eq!(sorted, expected);
+    let sensor = TypedArrayPtr::alloc(&vec![0.5, 0.25, 0.125, 2.0], &env)?;
+    assert_eq!(sum_floats.call(&mut *env.store(), *sensor)?, 2.875);
+    {
+        let store = env.store();
+        let memory = instance.exports.get_memory("memory")?.view(&*store);
+        assert_eq!(sensor.view(&memory)?.index(3).read()?, 2.0);
+    }
 
-    // Now checking with odd size
-    let input: Vec<u8> = vec![0x03, 0x02, 0x00, 0x01, 0x09];
-    let buffer_ptr = BufferPtr::alloc(&input, &env)?;
-    assert_eq!(buffer_ptr.size(memory)?, 5);
+    let sub = sensor.subarray(1, 3, &env)?;
+    assert_eq!(sub.read(&env)?, vec![0.25, 0.125]);
+    assert_eq!(sum_floats.call(&mut *env.store(), *sub)?, 0.375);
 
+    // writing in place is seen through the views sharing the buffer
+    let mut sub = *sub;
+    sub.write(&vec![1.0, 1.0], &env)?;
+    assert_eq!(sensor.read(&env)?, vec![0.5, 1.0, 1.0, 2.0]);
+
+    let bytes = TypedArrayPtr::<u8>::alloc(&vec![1, 2, 3], &env)?;
+    assert_eq!(bytes.read(&env)?, vec![1, 2, 3]);
     Ok(())
 }
 
-lazy_static::lazy_static! {
-    // static variable containing the printed values in test [read_write_any]
-    static ref ANY_PRINTED: std::sync::Arc<Mutex<Vec<i32>>> = std::sync::Arc::new(Mutex::new(Vec::new()));
+#[derive(AsClass)]
+#[as_class(id = 12)]
+struct Person {
+    name: StringPtr,
+    age: u8,
+    scores: Option<ArrayPtr<i32>>,
+}
+
+/// `Person` without its class id
+#[derive(AsClass)]
+struct Unknown {
+    name: StringPtr,
+    age: u8,
+    scores: Option<ArrayPtr<i32>>,
 }
 
 #[test]
-fn read_write_any() -> Result<(), Box<dyn Error>> {
-    fn print(val: i32) {
-        ANY_PRINTED.lock().unwrap().push(val);
-    }
-    let wasm_bytes = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/my_struct.wasm"));
-    // First get the exported object from a first module instance
-    let exported = {
-        let store = Store::default();
+fn classes() -> Result<(), Box<dyn Error>> {
+    let wasm_bytes = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/collections.wat"
+    ));
+    let mut store = Store::default();
+    let module = Module::new(&store, wasm_bytes)?;
+
+    let env = FunctionEnv::new(&mut store, Env::default());
+    let import_object = imports! {
+        "env" => {
+            "abort" => Function::new_typed_with_env(&mut store, &env, abort),
+        },
+    };
+
+    let instance = Instance::new(&mut store, &module, &import_object)?;
+
+    let env = EnvMut::new(env.into_mut(&mut store));
+    env.init(&instance)?;
+
+    let get_person = instance
+        .exports
+        .get_typed_function::<(), PersonPtr>(&*env.store(), "getPerson")?;
+    let person_score = instance
+        .exports
+        .get_typed_function::<PersonPtr, i32>(&*env.store(), "personScore")?;
+
+    let ptr = get_person.call(&mut *env.store())?;
+    let person = ptr.read(&env)?;
+    assert_eq!(ptr.size(&env)?, Person::size());
+    assert_eq!(AnyPtr::new(ptr.offset()).id(&env)?, 12);
+    assert_eq!(person.name.read(&env)?, "Alice");
+    assert_eq!(person.age, 30);
+    assert_eq!(person.scores.expect("scores").read(&env)?, vec![1, 2, 3]);
+
+    let mut bob = PersonPtr::alloc(
+        &Person {
+            name: *StringPtr::alloc(&"Bob".to_string(), &env)?,
+            age: 40,
+            scores: None,
+        },
+        &env,
+    )?;
+    assert_eq!(person_score.call(&mut *env.store(), *bob)?, 40);
+
+    let mut older = bob.read(&env)?;
+    older.age += 1;
+    let scores = ArrayPtr::alloc(&vec![10, 20], &env)?;
+    older.scores = Some(*scores);
+    bob.write(&older)?;
+    assert_eq!(person_score.call(&mut *env.store(), *bob)?, 71);
+    let read = bob.read(&env)?;
+    assert_eq!(read.name.read(&env)?, "Bob");
+    assert_eq!(read.scores.expect("scores").read(&env)?, vec![10, 20]);
+
+    let unknown = Unknown {
+        name: older.name,
+        age: 0,
+        scores: None,
+    };
+    assert!(matches!(
+        UnknownPtr::alloc(&unknown, &env),
+        Err(AsError::MissingClassId("Unknown"))
+    ));
+    Ok(())
+}
+
+#[test]
+fn memory_snapshots() -> Result<(), Box<dyn Error>> {
+    let wasm_bytes = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/collections.wat"
+    ));
+    // Dump the memory, then decode it once the instance is gone
+    let (dump, rtti, strings_ptr, map, person) = {
+        let mut store = Store::default();
+        let module = Module::new(&store, wasm_bytes)?;
+
+        let env = FunctionEnv::new(&mut store, Env::default());
         let import_object = imports! {
             "env" => {
-                "abort" => Function::new_native_with_env(&store, Env::default(), abort),
+                "abort" => Function::new_typed_with_env(&mut store, &env, abort),
             },
-            "index" => {
-                "print" => Function::new_native(&store, print),
-            }
         };
-        let module = Module::new(&store, wasm_bytes)?;
-        let instance = Instance::new(&module, &import_object)?;
-        let memory = instance.exports.get_memory("memory").expect("get memory");
 
-        let mut env = Env::default();
+        let instance = Instance::new(&mut store, &module, &import_object)?;
+
+        let env = EnvMut::new(env.into_mut(&mut store));
         env.init(&instance)?;
 
-        let get_struct = instance
-            .exports
-            .get_native_function::<(), AnyPtr>("get_struct")?;
+        let strings: ArrayPtr<StringPtr> = env.call("getStrings", ())?;
+        let map: MapPtr<StringPtr, i32> = env.call("getMap", ())?;
+        let person: PersonPtr = env.call("getPerson", ())?;
+        let mut dump = vec![0; usize::try_from(env.data_size())?];
+        env.read_bytes(0, &mut dump)?;
+        (dump, env.rtti()?, strings, map, person)
+    };
+
+    let strings = strings_ptr
+        .read(&dump)?
+        .iter()
+        .map(|ptr| ptr.read(&dump))
+        .collect::<Result<Vec<String>, _>>()?;
+    assert_eq!(strings, vec!["hello", "test"]);
+
+    let entries = map
+        .read(&dump)?
+        .into_iter()
+        .map(|(key, value)| Ok((key.read(&dump[..])?, value)))
+        .collect::<Result<Vec<_>, AsError>>()?;
+    assert_eq!(
+        entries,
+        vec![("one".to_string(), 1), ("three".to_string(), 3)]
+    );
+
+    let id = AnyPtr::new(person.offset()).id(&dump)?;
+    assert_eq!(id, 12);
+    assert!(!rtti.type_info(id, &dump)?.is_array());
+    let alice = person.read(&dump)?;
+    assert_eq!(alice.name.read(&dump)?, "Alice");
+    assert_eq!(alice.age, 30);
+    assert_eq!(alice.scores.expect("scores").read(&dump)?, vec![1, 2, 3]);
+
+    // A snapshot can't be written and ends with the dump
+    assert!(matches!(
+        dump.write_bytes(alice.name.offset(), &[0, 0]),
+        Err(AsError::ReadOnly)
+    ));
+    let outside = u32::try_from(dump.len())? + 4;
+    match BufferPtr::new(outside).read(&dump[..]) {
+        Err(AsError::OutOfBounds {
+            offset,
+            memory_size,
+        }) => {
+            assert_eq!(offset, outside);
+            assert_eq!(memory_size, dump.len() as u64);
+        }
+        _ => panic!("expected an out of bounds offset"),
+    }
+
+    // Corrupt headers fail instead of wrapping around the address space
+    let mut corrupt = dump.clone();
+    let length = usize::try_from(strings_ptr.offset())? + 12;
+    corrupt[length..length + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(matches!(
+        strings_ptr.read(&corrupt),
+        Err(AsError::OutOfBounds { .. })
+    ));
+    let entries = usize::try_from(map.offset())? + 8;
+    corrupt[entries..entries + 4].copy_from_slice(&(u32::MAX - 8).to_le_bytes());
+    assert!(matches!(
+        map.read(&corrupt),
+        Err(AsError::OutOfBounds { .. })
+    ));
+    assert!(matches!(
+        ArrayPtr::<i32>::new(u32::MAX - 4).len(&dump),
+        Err(AsError::OutOfBounds { offset, .. }) if offset == u32::MAX - 4
+    ));
+    Ok(())
+}
+
+#[test]
+fn host_imports() -> Result<(), Box<dyn Error>> {
+    let wasm_bytes = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/host_imports.wat"
+    ));
+    let mut store = Store::default();
+    let module = Module::new(&store, wasm_bytes)?;
+
+    let traces = Arc::new(Mutex::new(vec![]));
+    let sink = traces.clone();
+    let env = Env::default()
+        .with_trace(move |message, args| {
+            sink.lock()
+                .unwrap()
+                .push((message.to_string(), args.to_vec()))
+        })
+        .with_seed(42.);
+    let env = FunctionEnv::new(&mut store, env);
+    let import_object = imports(&mut store, &env);
+    let instance = Instance::new(&mut store, &module, &import_object)?;
+    // The imports read the strings given by the guest
+    EnvMut::new(env.into_mut(&mut store)).init(&instance)?;
+
+    instance
+        .exports
+        .get_typed_function::<(), ()>(&store, "traceHello")?
+        .call(&mut store)?;
+    assert_eq!(
+        *traces.lock().unwrap(),
+        vec![("hello".to_string(), vec![1.5, 2.])]
+    );
+
+    let get_seed = instance
+        .exports
+        .get_typed_function::<(), f64>(&store, "getSeed")?;
+    assert_eq!(get_seed.call(&mut store)?, 42.);
+
+    let before = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as f64;
+    let now = instance
+        .exports
+        .get_typed_function::<(), f64>(&store, "now")?
+        .call(&mut store)?;
+    assert!(now >= before && now < before + 60_000.);
+    Ok(())
+}
 
-        get_struct.call()?.export(memory)?
+#[test]
+fn abort_handlers() -> Result<(), Box<dyn Error>> {
+    let wasm_bytes = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/host_imports.wat"
+    ));
+    let mut store = Store::default();
+    let module = Module::new(&store, wasm_bytes)?;
+    let expected = AbortError {
+        message: "hello".to_string(),
+        file: "file.ts".to_string(),
+        line: 3,
+        col: 7,
     };
-    {
-        let store = Store::default();
-        let import_object = imports! {
-            "env" => {
-                "abort" => Function::new_native_with_env(&store, Env::default(), abort),
-            },
-            "index" => {
-                "print" => Function::new_native(&store, print),
-            }
-        };
-        let module = Module::new(&store, wasm_bytes)?;
-        let instance = Instance::new(&module, &import_object)?;
-        instance.exports.get_memory("memory").expect("get memory");
 
-        let mut env = Env::default();
+    // The guest stops by default
+    let env = FunctionEnv::new(&mut store, Env::default());
+    let import_object = imports(&mut store, &env);
+    let instance = Instance::new(&mut store, &module, &import_object)?;
+    EnvMut::new(env.into_mut(&mut store)).init(&instance)?;
+    let fail = instance
+        .exports
+        .get_typed_function::<(), i32>(&store, "fail")?;
+    match fail.call(&mut store) {
+        Err(err) => assert_eq!(err.downcast::<AbortError>()?, expected),
+        Ok(_) => panic!("expected the guest to abort"),
+    }
+
+    let aborts = Arc::new(Mutex::new(vec![]));
+    let callback = aborts.clone();
+    let env = Env::default().with_abort(AbortHandler::callback(move |abort| {
+        callback.lock().unwrap().push(abort.clone())
+    }));
+    let env = FunctionEnv::new(&mut store, env);
+    let import_object = imports(&mut store, &env);
+    let instance = Instance::new(&mut store, &module, &import_object)?;
+    EnvMut::new(env.into_mut(&mut store)).init(&instance)?;
+    let fail = instance
+        .exports
+        .get_typed_function::<(), i32>(&store, "fail")?;
+    assert_eq!(fail.call(&mut store)?, 1);
+    assert_eq!(*aborts.lock().unwrap(), vec![expected]);
+    Ok(())
+}
+
+#[test]
+fn guest_errors() -> Result<(), Box<dyn Error>> {
+    let wasm_bytes = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/host_imports.wat"
+    ));
+    let mut store = Store::default();
+    let module = Module::new(&store, wasm_bytes)?;
+
+    // Both the trap of `abort` and the `unreachable` following it
+    for env in [
+        Env::default(),
+        Env::default().with_abort(AbortHandler::callback(|_| {})),
+    ] {
+        let env = FunctionEnv::new(&mut store, env);
+        let import_object = imports(&mut store, &env);
+        let instance = Instance::new(&mut store, &module, &import_object)?;
+        let assert: TypedFunction<i32, ()> =
+            instance.exports.get_typed_function(&store, "assert")?;
+        let env = EnvMut::new(env.into_mut(&mut store));
         env.init(&instance)?;
 
-        let print_vals = instance.exports.get_native_function::<i32, ()>("dump")?;
-        let ptr = AnyPtr::import(&exported, &env)?.offset();
-        assert_eq!(
-            exported.id,
-            AnyPtr::new(ptr).export(env.memory.get_ref().unwrap())?.id
-        );
-        print_vals.call(ptr as i32)?;
+        env.catch(|store| assert.call(store, 1))?;
+        assert_eq!(env.last_abort(), None);
+        let err = env.catch(|store| assert.call(store, 0)).expect_err("abort");
+        assert!(err
+            .to_string()
+            .starts_with("hello at file.ts:3:7\n    at assert"));
+        match err {
+            AsError::Abort { abort, trace } => {
+                assert_eq!(abort.line, 3);
+                assert_eq!(trace[0].function_name(), Some("assert"));
+            }
+            _ => panic!("expected an abort"),
+        }
+        assert!(env.last_abort().is_some());
+    }
+
+    // Other traps are left as is
+    let env = FunctionEnv::new(&mut store, Env::default());
+    let import_object = imports(&mut store, &env);
+    let instance = Instance::new(&mut store, &module, &import_object)?;
+    let fail: TypedFunction<(), i32> = instance.exports.get_typed_function(&store, "fail")?;
+    let env = EnvMut::new(env.into_mut(&mut store));
+    env.init(&instance)?;
+    let err = env
+        .catch(|_| -> Result<(), RuntimeError> { Err(RuntimeError::new("oops")) })
+        .expect_err("trap");
+    assert!(matches!(err, AsError::Runtime(_)));
+    assert!(matches!(
+        env.catch(|store| fail.call(store)),
+        Err(AsError::Abort { .. })
+    ));
+    Ok(())
+}
+
+#[test]
+fn typed_calls() -> Result<(), Box<dyn Error>> {
+    let wasm_bytes = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/collections.wat"
+    ));
+    let mut store = Store::default();
+    let module = Module::new(&store, wasm_bytes)?;
+
+    let env = FunctionEnv::new(&mut store, Env::default());
+    let import_object = imports(&mut store, &env);
+    let instance = Instance::new(&mut store, &module, &import_object)?;
+    let env = EnvMut::new(env.into_mut(&mut store));
+    env.init(&instance)?;
+
+    let pinned = |offset: u32| -> Result<bool, AsError> {
+        let mut gc_info = [0; 4];
+        env.read_bytes(offset - 16, &mut gc_info)?;
+        Ok(u32::from_le_bytes(gc_info) & 3 == 3)
     };
 
-    let p = ANY_PRINTED.lock().unwrap();
-    let v = p.clone();
-    assert_eq!(v, vec![12, 13, 12, 13]);
+    let output: String = env.call("process", ("hello", &[1u8, 2, 3][..]))?;
+    assert_eq!(output, "hello");
+    let len: i32 = env.call("byteLength", (vec![0u8; 5],))?;
+    assert_eq!(len, 5);
+
+    // The arguments are unpinned once the call returns
+    let ptr: StringPtr = env.call("process", ("unpinned".to_string(), BufferPtr::new(0)))?;
+    assert_eq!(ptr.read(&env)?, "unpinned");
+    assert!(!pinned(ptr.offset())?);
+
+    let ints = ArrayPtr::alloc(&vec![1, 2, 3], &env)?;
+    let sum: i32 = env.call("sumInts", (*ints,))?;
+    assert_eq!(sum, 6);
+
+    // Even if it traps
+    assert!(matches!(
+        env.call::<_, ()>("failWith", ("fail",)),
+        Err(AsError::Runtime(_))
+    ));
+    let failure = instance
+        .exports
+        .get_global("lastFailure")?
+        .get(&mut *env.store())
+        .unwrap_i32() as u32;
+    assert_eq!(StringPtr::new(failure).read(&env)?, "fail");
+    assert!(!pinned(failure)?);
+
+    assert!(matches!(
+        env.call::<_, f64>("sumInts", (*ints,)),
+        Err(AsError::Signature)
+    ));
+    assert!(matches!(
+        env.call::<_, ()>("unknown", ()),
+        Err(AsError::MissingExport("unknown"))
+    ));
+    Ok(())
+}
+
+#[test]
+fn host_functions() -> Result<(), Box<dyn Error>> {
+    // runtime_exported.wat, calling an imported `log` from `callLog`
+    let wat = std::str::from_utf8(RUNTIME_EXPORTED)?
+        .replacen(
+            " (global ",
+            " (import \"env\" \"log\" (func $log (param i32 i32) (result i32)))\n (global ",
+            1,
+        )
+        .replacen(
+            " (start $~start)",
+            " (export \"callLog\" (func $callLog))\n (start $~start)\n (func $callLog (param i32 i32) (result i32)\n  local.get 0\n  local.get 1\n  call $log\n )",
+            1,
+        );
+    let mut store = Store::default();
+    let module = Module::new(&store, wat)?;
+
+    fn log(message: String, data: Vec<u8>) -> String {
+        format!("{}: {:?}", message, data)
+    }
+
+    let env = FunctionEnv::new(&mut store, Env::default());
+    let mut import_object = imports(&mut store, &env);
+    let mut exports = env_exports(&mut store, &env);
+    exports.insert("log", host_function(&mut store, &env, log));
+    import_object.register_namespace("env", exports);
+    let instance = Instance::new(&mut store, &module, &import_object)?;
+
+    let env = EnvMut::new(env.into_mut(&mut store));
+    env.init(&instance)?;
+    let output: String = env.call("callLog", ("hello", &[1u8, 2][..]))?;
+    assert_eq!(output, "hello: [1, 2]");
+
+    // The parameters which can't be read trap with the error
+    let call_log = instance
+        .exports
+        .get_typed_function::<(i32, i32), i32>(&*env.store(), "callLog")?;
+    let err = call_log
+        .call(&mut *env.store(), 1 << 20, 0)
+        .expect_err("out of bounds");
+    assert!(matches!(
+        err.downcast::<AsError>()?,
+        AsError::OutOfBounds { .. }
+    ));
+    Ok(())
+}
+
+#[allow(dead_code)]
+mod bindings {
+    include!("bindings/collections.rs");
+    include!("bindings/narrow.rs");
+}
+
+/// The lines changed from `old` to `new`, with their number.
+fn diff_lines(old: &str, new: &str) -> String {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    (0..old.len().max(new.len()))
+        .filter(|i| old.get(*i) != new.get(*i))
+        .map(|i| {
+            format!(
+                "{}\n- {}\n+ {}",
+                i + 1,
+                old.get(i).unwrap_or(&""),
+                new.get(i).unwrap_or(&"")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn generated_bindings() -> Result<(), Box<dyn Error>> {
+    // The bindings are generated from the declarations, and kept in the tree
+    // to be compiled with the tests
+    for module in ["collections", "narrow"] {
+        let declarations = format!("{}/tests/{}.d.ts", env!("CARGO_MANIFEST_DIR"), module);
+        let path = format!(
+            "{}/tests/bindings/{}.rs",
+            env!("CARGO_MANIFEST_DIR"),
+            module
+        );
+        let generated = Bindings::from_file(&declarations)?.to_rust();
+        let kept = std::fs::read_to_string(&path)?;
+        assert!(
+            kept == generated,
+            "{} is outdated, generate it again from {}:\n{}",
+            path,
+            declarations,
+            diff_lines(&kept, &generated)
+        );
+    }
+
+    let wasm_bytes = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/collections.wat"
+    ));
+    let mut store = Store::default();
+    let module = Module::new(&store, wasm_bytes)?;
+
+    let env = FunctionEnv::new(&mut store, Env::default());
+    let import_object = imports(&mut store, &env);
+    let instance = Instance::new(&mut store, &module, &import_object)?;
+    let collections = bindings::Collections::new(&instance, &store)?;
+    let env = EnvMut::new(env.into_mut(&mut store));
+    env.init(&instance)?;
+
+    let strings = collections.get_strings(&mut *env.store())?;
+    let strings = strings.read(&env)?;
+    assert_eq!(strings.len(), 2);
+    assert_eq!(strings[0].read(&env)?, "hello");
+    let ints = ArrayPtr::alloc(&vec![1, 2, 3], &env)?;
+    assert_eq!(collections.sum_ints(&mut *env.store(), *ints)?, 6);
+    let set = collections.get_set(&mut *env.store())?;
+    assert!(collections.set_has(&mut *env.store(), set, 2)?);
+    assert!(!collections.set_has(&mut *env.store(), set, 4)?);
+    let person = collections.get_person(&mut *env.store())?;
+    assert_eq!(AnyPtr::id(&person, &env)?, 12);
+
+    // The integers narrower than `i32` are given as `i32` to wasm
+    let mut store = Store::default();
+    let module = Module::new(
+        &store,
+        r#"(module
+            (func (export "negate") (param i32) (result i32)
+                (i32.sub (i32.const 0) (local.get 0))))"#,
+    )?;
+    let instance = Instance::new(&mut store, &module, &imports! {})?;
+    let narrow = bindings::Narrow::new(&instance, &store)?;
+    assert_eq!(narrow.negate(&mut store, 200)?, -200);
+    assert_eq!(narrow.negate(&mut store, 255)?, -255);
+
+    // Declarations of older compilers, with the AssemblyScript types
+    let bindings = Bindings::parse(
+        "Legacy",
+        "declare module ASModule {\n  type i32 = number;\n  export function add(a: i32, b: i32): i32;\n  export function hello(name: usize): void;\n}\nexport default ASModule;\n",
+    );
+    assert_eq!(
+        bindings.functions,
+        vec![
+            ExportFn {
+                name: "add".to_string(),
+                params: vec![
+                    ("a".to_string(), "i32".to_string()),
+                    ("b".to_string(), "i32".to_string())
+                ],
+                result: "i32".to_string(),
+            },
+            ExportFn {
+                name: "hello".to_string(),
+                params: vec![("name".to_string(), "usize".to_string())],
+                result: "void".to_string(),
+            },
+        ]
+    );
+    assert!(bindings
+        .to_rust()
+        .contains("pub fn hello(&self, store: &mut impl ::wasmer::AsStoreMut, name: u32) -> Result<(), ::wasmer::RuntimeError>"));
+    Ok(())
+}
+
+#[test]
+fn module_info() -> Result<(), Box<dyn Error>> {
+    let store = Store::default();
+
+    let module = Module::new(&store, RUNTIME_EXPORTED)?;
+    let info = ModuleInfo::new(&module);
+    info.check()?;
+    assert_eq!(info.runtime(), Some(RuntimeVariant::Incremental));
+    assert_eq!(
+        info.runtime_exports(),
+        vec!["__new", "__pin", "__unpin", "__collect"]
+    );
+    assert!(info.exports_runtime());
+    let get_string = info.function("getString").expect("getString");
+    assert!(get_string.params().is_empty());
+    assert_eq!(get_string.results(), [WasmType::I32]);
+    assert_eq!(info.functions().count(), 5);
+
+    let wasm_bytes = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_wat.wat"));
+    let info = ModuleInfo::new(&Module::new(&store, wasm_bytes)?);
+    assert!(matches!(info.check(), Err(AsError::MissingExport("__new"))));
+    assert_eq!(info.runtime(), None);
+    assert!(info.runtime_exports().is_empty());
+    assert!(!info.exports_runtime());
+
+    let info = ModuleInfo::new(&Module::new(&store, "(module)")?);
+    assert!(matches!(
+        info.check(),
+        Err(AsError::MissingExport("memory"))
+    ));
+
+    // A debug build names the functions of its runtime, a release build
+    // has no name section
+    let info = ModuleInfo::new(&Module::new(&store, MY_STRUCT)?);
+    assert_eq!(info.runtime(), Some(RuntimeVariant::Incremental));
+    let info = ModuleInfo::new(&Module::new(&store, BUFFER)?);
+    assert_eq!(info.runtime(), None);
+
+    // The runtime functions must have their signature
+    let info = ModuleInfo::new(&Module::new(
+        &store,
+        r#"(module
+            (memory (export "memory") 1)
+            (func (export "__new") (param i32 i32) (result i32) i32.const 0)
+            (func (export "__pin") (param i32))
+            (func (export "__unpin") (param i32))
+            (func (export "__collect")))"#,
+    )?);
+    assert!(matches!(
+        info.check(),
+        Err(AsError::ExportSignature("__pin"))
+    ));
+    assert_eq!(info.runtime(), None);
+    Ok(())
+}
+
+#[test]
+fn borrowed_strings() -> Result<(), Box<dyn Error>> {
+    Wasmer::run(RUNTIME_EXPORTED, |env, _| {
+        // A surrogate pair on the edge of the chunks read at once
+        let value = format!("{}\u{1D306}{}", "a".repeat(255), "é".repeat(300));
+        let ptr = StringPtr::alloc(&value, env)?;
+        assert_eq!(ptr.chars(env)?.collect::<String>(), value);
+        assert_eq!(ptr.read(env)?, value);
+        assert!(ptr.eq_str(env, &value)?);
+        assert!(!ptr.eq_str(env, &value[1..])?);
+        assert!(!ptr.eq_str(env, &value.replace('é', "e"))?);
+
+        let mut buf = String::from("key: ");
+        let hello = StringPtr::alloc(&"hello".to_string(), env)?;
+        hello.write_into(env, &mut buf)?;
+        assert_eq!(buf, "key: hello");
+        buf.clear();
+        hello.write_into(env, &mut buf)?;
+        assert_eq!(buf, "hello");
+        assert!(hello.eq_str(env, "hello")?);
+        assert!(!hello.eq_str(env, "hellp")?);
+
+        // Unpaired surrogates are replaced
+        env.write_bytes(hello.offset(), &0xD800u16.to_le_bytes())?;
+        env.write_bytes(hello.offset() + 6, &0xDC00u16.to_le_bytes())?;
+        assert_eq!(hello.chars(env)?.collect::<String>(), "\u{FFFD}el\u{FFFD}o");
+
+        let outside = StringPtr::new(u32::try_from(env.data_size())? - 2);
+        assert!(matches!(
+            outside.chars(env),
+            Err(AsError::OutOfBounds { .. })
+        ));
+        Ok(())
+    })
+}
+
+#[test]
+fn unpaired_surrogates() -> Result<(), Box<dyn Error>> {
+    Wasmer::run(RUNTIME_EXPORTED, |env, _| {
+        let ptr = StringPtr::alloc(&"hello".to_string(), env)?;
+        env.write_bytes(ptr.offset(), &0xD800u16.to_le_bytes())?;
+        env.write_bytes(ptr.offset() + 6, &0xDC00u16.to_le_bytes())?;
+
+        assert_eq!(ptr.read(env)?, "\u{FFFD}el\u{FFFD}o");
+        assert!(matches!(
+            ptr.read_strict(env),
+            Err(AsError::InvalidUtf16 { offset: 0 })
+        ));
+        assert_eq!(ptr.read_utf16(env)?, [0xD800, 0x65, 0x6C, 0xDC00, 0x6F]);
+        assert_eq!(ptr.read_wtf8(env)?, b"\xED\xA0\x80el\xED\xB0\x80o".to_vec());
+
+        env.data_mut().utf16 = Utf16Policy::Strict;
+        assert!(matches!(
+            ptr.read(env),
+            Err(AsError::InvalidUtf16 { offset: 0 })
+        ));
+        env.write_bytes(ptr.offset(), &u16::from(b'h').to_le_bytes())?;
+        assert!(matches!(
+            ptr.read(env),
+            Err(AsError::InvalidUtf16 { offset: 6 })
+        ));
+        env.write_bytes(ptr.offset() + 6, &u16::from(b'l').to_le_bytes())?;
+        assert_eq!(ptr.read(env)?, "hello");
+
+        // Raw keeps the surrogates, except in a `String`
+        env.write_bytes(ptr.offset() + 2, &0xDFFFu16.to_le_bytes())?;
+        env.data_mut().utf16 = Utf16Policy::Lossy;
+        assert_eq!(ptr.read_text(env)?, "h\u{FFFD}llo".as_bytes());
+        env.data_mut().utf16 = Utf16Policy::Raw;
+        assert_eq!(ptr.read_text(env)?, b"h\xED\xBF\xBFllo".to_vec());
+        assert!(matches!(
+            ptr.read(env),
+            Err(AsError::InvalidUtf16 { offset: 2 })
+        ));
+        env.data_mut().utf16 = Utf16Policy::Strict;
+        assert!(matches!(
+            ptr.read_text(env),
+            Err(AsError::InvalidUtf16 { offset: 2 })
+        ));
+
+        // Valid pairs are decoded, even on the edge of a chunk
+        let value = format!("{}\u{1D306}", "a".repeat(255));
+        let pair = StringPtr::alloc(&value, env)?;
+        assert_eq!(pair.read(env)?, value);
+        assert_eq!(pair.read_wtf8(env)?, value.as_bytes());
+        Ok(())
+    })
+}
+
+#[test]
+fn unicode_strings() -> Result<(), Box<dyn Error>> {
+    use proptest::prelude::*;
+    use proptest::test_runner::{TestCaseError, TestRunner};
+
+    Wasmer::run(RUNTIME_EXPORTED, |env, call| {
+        // Arbitrary strings, and strings of CJK, emoji and combining marks
+        let strings = prop_oneof![
+            any::<String>(),
+            "[a-z\u{300}-\u{36F}\u{4E00}-\u{9FFF}\u{1F300}-\u{1FAFF}]{0,64}",
+        ];
+        let fail = |err: AsError| TestCaseError::fail(err.to_string());
+        let mut runner = TestRunner::default();
+        runner.run(&strings, |value| {
+            let mut ptr = StringPtr::alloc(&value, env).map_err(fail)?;
+            call("__collect", &[]).map_err(|err| TestCaseError::fail(err.to_string()))?;
+            let len = value.encode_utf16().count() as u32;
+            prop_assert_eq!(ptr.utf16_len(env).map_err(fail)?, len);
+            prop_assert_eq!(ptr.read(env).map_err(fail)?, value.clone());
+            prop_assert!(ptr.eq_str(env, &value).map_err(fail)?);
+
+            // Same length, written in place
+            let reversed: String = value.chars().rev().collect();
+            let offset = ptr.offset();
+            ptr.write(&reversed).map_err(fail)?;
+            prop_assert_eq!(ptr.offset(), offset);
+            prop_assert_eq!(ptr.read(env).map_err(fail)?, reversed);
+
+            // A character outside of the BMP adds 2 code units
+            let longer = format!("{}\u{1D306}", value);
+            ptr.write(&longer).map_err(fail)?;
+            prop_assert_eq!(ptr.utf16_len(env).map_err(fail)?, len + 2);
+            prop_assert_eq!(ptr.read(env).map_err(fail)?, longer);
+            Ok(())
+        })?;
+        Ok(())
+    })
+}
+
+#[test]
+fn byte_streams() -> Result<(), Box<dyn Error>> {
+    use std::io::{self, Read as _, Seek, SeekFrom, Write as _};
+
+    Wasmer::run(RUNTIME_EXPORTED, |env, _| {
+        let content: Vec<u8> = (0..100_000).map(|i| (i % 251) as u8).collect();
+        let buffer_ptr = BufferPtr::alloc(&content, env)?;
+        let mut stream = buffer_ptr.stream(env);
+        let mut copy = Vec::new();
+        io::copy(&mut stream, &mut copy)?;
+        assert_eq!(copy, content);
+        assert_eq!(stream.read(&mut [0; 16])?, 0);
+
+        assert_eq!(stream.seek(SeekFrom::End(-10))?, 99_990);
+        let mut tail = Vec::new();
+        stream.read_to_end(&mut tail)?;
+        assert_eq!(tail, content[99_990..]);
+        assert!(stream.seek(SeekFrom::Current(-200_000)).is_err());
+
+        stream.seek(SeekFrom::Start(5))?;
+        stream.write_all(b"abc")?;
+        assert_eq!(stream.position(), 8);
+        let written = buffer_ptr.read(env)?;
+        assert_eq!(written[..8], [0, 1, 2, 3, 4, b'a', b'b', b'c']);
+        assert_eq!(written[8..], content[8..]);
+
+        // The buffer doesn't grow
+        stream.seek(SeekFrom::End(-2))?;
+        let err = stream.write_all(b"abc").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::WriteZero);
+        assert_eq!(buffer_ptr.size(env)?, 100_000);
+        Ok(())
+    })?;
+
+    // A StaticArray<u32> in a snapshot of the memory
+    let mut dump = vec![0; 16];
+    dump.extend_from_slice(&12u32.to_le_bytes());
+    dump.extend([1u32, 2, 3].iter().flat_map(|v| v.to_le_bytes()));
+    let array_ptr = StaticArrayPtr::<u32>::new(20);
+    let mut bytes = Vec::new();
+    array_ptr.stream(&dump).read_to_end(&mut bytes)?;
+    assert_eq!(bytes, dump[20..]);
+    assert!(array_ptr.stream(&dump).write(&[0]).is_err());
     Ok(())
 }